@@ -1,4 +1,4 @@
-use aoc2024::{AocHelper, RequestedAocInputType};
+use aoc2024::{parse::Span, runner::run_standalone, solution::Solution, AocResult};
 use std::{collections::BTreeMap, iter::zip};
 struct Day1PartA {
     raw: String,
@@ -15,14 +15,13 @@ impl Day1PartA {
         }
     }
 
-    pub fn parse(&mut self) {
-        self.raw.lines().for_each(|line| {
-            let mut parts = line.split_ascii_whitespace();
-            self.a_vec
-                .push(parts.next().unwrap().parse::<i32>().unwrap());
-            self.b_vec
-                .push(parts.next().unwrap().parse::<i32>().unwrap());
-        });
+    pub fn parse(&mut self) -> AocResult<()> {
+        for line in Span::new(&self.raw).lines() {
+            let [a, b] = line.ints_n()?;
+            self.a_vec.push(a);
+            self.b_vec.push(b);
+        }
+        Ok(())
     }
 
     pub fn sort_lists(&mut self) {
@@ -52,17 +51,16 @@ impl Day1PartB {
         }
     }
 
-    pub fn parse(&mut self) {
-        self.raw.lines().for_each(|line| {
-            let mut parts = line.split_ascii_whitespace();
-            self.a_vec
-                .push(parts.next().unwrap().parse::<i32>().unwrap());
-            let b = parts.next().unwrap().parse::<i32>().unwrap();
+    pub fn parse(&mut self) -> AocResult<()> {
+        for line in Span::new(&self.raw).lines() {
+            let [a, b] = line.ints_n()?;
+            self.a_vec.push(a);
             self.b_counter
                 .entry(b)
                 .and_modify(|cur| *cur += 1)
                 .or_insert(1);
-        });
+        }
+        Ok(())
     }
 
     pub fn get_score(&self) -> i32 {
@@ -73,38 +71,40 @@ impl Day1PartB {
     }
 }
 
-fn main() {
-    let aoc: AocHelper = AocHelper::new(1, None);
-    let test_input: String = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .expect("Failed to read day1 test input");
-    let real_input: String = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .expect("Failed to read day1 real input");
-
-    let mut day1_part_a_test: Day1PartA = Day1PartA::new(&test_input);
-    day1_part_a_test.sort_lists();
-    day1_part_a_test.parse();
+#[derive(Default)]
+pub struct Day1Solution {
+    part_a: Option<Day1PartA>,
+    part_b: Option<Day1PartB>,
+}
 
-    let ans = day1_part_a_test.get_diff_sum();
-    println!("Found diff answer: {}", ans);
+impl Solution for Day1Solution {
+    fn day(&self) -> u32 {
+        1
+    }
 
-    let mut day1_part_a_real: Day1PartA = Day1PartA::new(&real_input);
-    day1_part_a_real.parse();
-    day1_part_a_real.sort_lists();
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let raw = input.to_string();
+        let mut part_a = Day1PartA::new(&raw);
+        part_a.parse()?;
+        let mut part_b = Day1PartB::new(&raw);
+        part_b.parse()?;
+        self.part_a = Some(part_a);
+        self.part_b = Some(part_b);
+        Ok(())
+    }
 
-    let ans = day1_part_a_real.get_diff_sum();
-    println!("Found diff answer: {}", ans);
+    fn part1(&mut self) -> Option<String> {
+        let part_a = self.part_a.as_mut()?;
+        part_a.sort_lists();
+        Some(part_a.get_diff_sum().to_string())
+    }
 
-    let mut day1_partb_test: Day1PartB = Day1PartB::new(&test_input);
-    day1_partb_test.parse();
-    println!("Counter: {:?}", day1_partb_test.b_counter);
-    let ans = day1_partb_test.get_score();
-    println!("Score: {}", ans);
+    fn part2(&mut self) -> Option<String> {
+        let part_b = self.part_b.as_ref()?;
+        Some(part_b.get_score().to_string())
+    }
+}
 
-    let mut day1_partb_real: Day1PartB = Day1PartB::new(&real_input);
-    day1_partb_real.parse();
-    println!("Counter: {:?}", day1_partb_real.b_counter);
-    let ans = day1_partb_real.get_score();
-    println!("Score: {}", ans);
+fn main() {
+    run_standalone::<Day1Solution>();
 }
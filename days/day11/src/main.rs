@@ -1,6 +1,8 @@
 
-use aoc2024::{counter::BTreeCounter, AocHelper, RequestedAocInputType};
-use tracing::{debug, error, info};
+use aoc2024::{
+    counter::BTreeCounter, parse_token_in, runner::run_standalone, solution::Solution, AocResult,
+};
+use tracing::{debug, error};
 
 // struct TreeNode {
 //     val: usize,
@@ -30,20 +32,18 @@ impl Day11 {
             stones: Vec::new(),
         }
     }
-    pub fn parse(&mut self) {
-        self.raw
-            .split_ascii_whitespace()
-            .into_iter()
-            .for_each(|num| {
-                let val = num.parse::<usize>().unwrap();
-                // let root = Day11::build_root_node_ref(val);
-                // self.roots.push(root)
-                let stone = Stone::Single {
-                    value: val,
-                    rep: val.to_string(),
-                };
-                self.stones.push(stone);
-            });
+    pub fn parse(&mut self) -> AocResult<()> {
+        for num in self.raw.split_ascii_whitespace() {
+            let val: usize = parse_token_in(&self.raw, num)?;
+            // let root = Day11::build_root_node_ref(val);
+            // self.roots.push(root)
+            let stone = Stone::Single {
+                value: val,
+                rep: val.to_string(),
+            };
+            self.stones.push(stone);
+        }
+        Ok(())
     }
 
     fn split_number(n: &str) -> ((usize, usize), (String, String)) {
@@ -143,14 +143,12 @@ impl D11Part2 {
         }
     }
 
-    pub fn parse(&mut self) {
-        self.raw
-            .split_ascii_whitespace()
-            .into_iter()
-            .for_each(|num| {
-                let val = num.parse::<usize>().unwrap();
-                self.stones.add(val)
-            });
+    pub fn parse(&mut self) -> AocResult<()> {
+        for num in self.raw.split_ascii_whitespace() {
+            let val: usize = parse_token_in(&self.raw, num)?;
+            self.stones.add(val)
+        }
+        Ok(())
     }
 
     fn split_number(n: &str) -> ((usize, usize), (String, String)) {
@@ -184,7 +182,7 @@ impl D11Part2 {
     }
 
     fn stone_length(&self) -> usize {
-        self.stones.iter().fold(0, |acc, (k, v)| acc + *v)
+        self.stones.total()
     }
 
     pub fn multi_blink(&mut self, steps: usize) -> usize {
@@ -198,44 +196,39 @@ impl D11Part2 {
     }
 }
 
+#[derive(Default)]
+pub struct Day11Solution {
+    d11: Option<Day11>,
+    d11_part2: Option<D11Part2>,
+}
+
+impl Solution for Day11Solution {
+    fn day(&self) -> u32 {
+        11
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let raw = input.to_string();
+        let mut d11 = Day11::new(&raw);
+        d11.parse()?;
+        let mut d11_part2 = D11Part2::new(&raw);
+        d11_part2.parse()?;
+        self.d11 = Some(d11);
+        self.d11_part2 = Some(d11_part2);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d11 = self.d11.as_mut()?;
+        Some(d11.multi_blink(25).to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d11 = self.d11_part2.as_mut()?;
+        Some(d11.multi_blink(75).to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(11, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d11 = Day11::new(&test_input);
-    d11.parse();
-    debug!("{:?}", d11);
-    d11.print_stones();
-    let ans = d11.multi_blink(25);
-    // info!("{}", d11.print_stones());
-    info!("Answer: {ans}");
-
-    // let mut d11 = Day11::new(&real_input);
-    // d11.parse();
-    // debug!("{:?}", d11);
-    // d11.print_stones();
-    // let ans = d11.multi_blink(25);
-    // // info!("{}", d11.print_stones());
-    // info!("Answer: {ans}");
-
-    let mut d11 = D11Part2::new(&test_input);
-    d11.parse();
-    debug!("{:?}", d11);
-    // d11.single_step();
-    // debug!("{:?}", d11);
-    let ans = d11.multi_blink(75);
-    info!("Answer: {ans}");
-
-    let mut d11 = D11Part2::new(&real_input);
-    d11.parse();
-    debug!("{:?}", d11);
-    // d11.single_step();
-    // debug!("{:?}", d11);
-    let ans = d11.multi_blink(75);
-    info!("Answer: {ans}")
+    run_standalone::<Day11Solution>();
 }
@@ -1,9 +1,12 @@
 use rayon::prelude::*;
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::BTreeSet;
 
 use aoc2024::{
-    map_vec_extend::{append_to_mapping_set},
-    AocHelper, RequestedAocInputType,
+    grid::Grid,
+    multimap::BTreeMultiMap,
+    runner::run_standalone,
+    solution::Solution,
+    AocResult,
 };
 use tracing::{debug, info};
 
@@ -15,47 +18,11 @@ type PointSet = BTreeSet<Point>;
 #[derive(Debug)]
 struct Day12 {
     raw: String,
-    point_sets: BTreeMap<char, PointSet>,
-    region_sets: BTreeMap<char, Vec<PointSet>>,
+    point_sets: BTreeMultiMap<char, PointSet>,
+    region_sets: BTreeMultiMap<char, Vec<PointSet>>,
     bounds: Point,
 }
 
-fn flood_from_seed(
-    seed: Point,
-    bounds: Point,
-    valids: &BTreeSet<Point>,
-    visited: Option<BTreeSet<Point>>,
-) -> Option<BTreeSet<Point>> {
-    let next = [
-        (seed.0, seed.1 + 1),
-        (seed.0, seed.1 - 1),
-        (seed.0 + 1, seed.1),
-        (seed.0 - 1, seed.1),
-    ];
-
-    let in_bounds = |p: &Point| p.0 >= 0 && p.0 < bounds.0 && p.1 >= 0 && p.1 < bounds.1;
-
-    let mut _visited;
-    if visited.is_none() {
-        _visited = BTreeSet::new();
-    } else {
-        _visited = visited.unwrap();
-    }
-
-    _visited.insert(seed);
-    next.iter().for_each(|p: &Point| {
-        if !in_bounds(p) || _visited.contains(p) || !valids.contains(p) {
-            return;
-        }
-        let res = flood_from_seed(*p, bounds, valids, Some(_visited.clone()));
-        if res.is_some() {
-            _visited.append(&mut res.unwrap().clone());
-        }
-    });
-
-    Some(_visited)
-}
-
 enum KernelMatch {
     Yes { i: usize },
     No { i: usize },
@@ -224,38 +191,12 @@ impl Day12 {
     pub fn new(s: &String) -> Self {
         Self {
             raw: s.clone(),
-            point_sets: BTreeMap::new(),
-            region_sets: BTreeMap::new(),
+            point_sets: BTreeMultiMap::new(),
+            region_sets: BTreeMultiMap::new(),
             bounds: (0, 0),
         }
     }
 
-    fn find_islands(&mut self, c: char) -> Option<Vec<PointSet>> {
-        let Some(all_points) = self.point_sets.get(&c) else {
-            return None;
-        };
-        let mut regions: Vec<PointSet> = Vec::new();
-        let mut non_regioned: PointSet = all_points.clone();
-        let seed = all_points.first().unwrap();
-        let mut res = flood_from_seed(*seed, self.bounds, all_points, None);
-        while res.is_some() {
-            debug!("Res of first flood for {}: {:?}", c, res);
-            for ele in res.clone().unwrap().iter() {
-                non_regioned.remove(ele);
-            }
-            regions.push(res.clone().unwrap());
-
-            let seed = non_regioned.first();
-            if seed.is_some() {
-                res = flood_from_seed(*seed.unwrap(), self.bounds, &non_regioned, None);
-            } else {
-                break;
-            }
-        }
-        info!("Regions: {:?}", regions);
-        Some(regions)
-    }
-
     fn calculate_perimeter(&self, s: &PointSet) -> usize {
         let mut perimeter = 0;
 
@@ -298,19 +239,21 @@ impl Day12 {
     }
 
     pub fn find_total_score_p1(&self) -> usize {
-        let chars: Vec<char> = self.point_sets.keys().clone().map(|k| *k).collect();
+        let chars: Vec<char> = self.point_sets.keys().copied().collect();
         chars
             .iter()
             .fold(0, |acc, k| acc + self.calculate_area_perimter_for_char(*k))
     }
 
-    pub fn find_all_islands(&mut self) {
-        let chars: Vec<char> = self.point_sets.keys().clone().map(|k| *k).collect();
-        chars.iter().for_each(|k| {
-            if let Some(res) = self.find_islands(*k) {
-                self.region_sets.insert(*k, res);
-            };
-        });
+    pub fn find_all_islands(&mut self) -> AocResult<()> {
+        let grid = Grid::parse(&self.raw, |c| c)?;
+        let regions = grid.components(|a, b| a == b);
+        for region in regions.iter() {
+            let points: PointSet = region.iter().map(|p| (p.0 as i32, p.1 as i32)).collect();
+            self.region_sets.insert(grid[region[0]], points);
+        }
+        info!("Found {} regions", regions.len());
+        Ok(())
     }
 
     pub fn parse(&mut self) {
@@ -319,7 +262,7 @@ impl Day12 {
         for (row, line) in self.raw.lines().enumerate() {
             for (col, char) in line.chars().enumerate() {
                 // debug!("Char {} @ ({}, {})", char, row, col);
-                append_to_mapping_set(&mut self.point_sets, char, (row as i32, col as i32));
+                self.point_sets.insert(char, (row as i32, col as i32));
                 mc = col;
             }
             mr = row
@@ -351,40 +294,42 @@ impl Day12 {
     }
 
     pub fn find_total_score_p2(&self) -> usize {
-        let chars: Vec<char> = self.point_sets.keys().clone().map(|k| *k).collect();
+        let chars: Vec<char> = self.point_sets.keys().copied().collect();
         chars
             .iter()
             .fold(0, |acc, k| acc + self.calculate_area_sides_for_char(*k))
     }
 }
 
+#[derive(Default)]
+pub struct Day12Solution {
+    d12: Option<Day12>,
+}
+
+impl Solution for Day12Solution {
+    fn day(&self) -> u32 {
+        12
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d12 = Day12::new(&input.to_string());
+        d12.parse();
+        d12.find_all_islands()?;
+        self.d12 = Some(d12);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d12 = self.d12.as_ref()?;
+        Some(d12.find_total_score_p1().to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d12 = self.d12.as_ref()?;
+        Some(d12.find_total_score_p2().to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(12, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d12 = Day12::new(&test_input);
-    d12.parse();
-    debug!("{:?}", d12);
-    d12.find_all_islands();
-    debug!("{:?}", d12);
-    let score = d12.find_total_score_p1();
-    info!("Score is {}", score);
-    let score = d12.find_total_score_p2();
-    info!("Score is {}", score);
-    // return;
-
-    let mut d12 = Day12::new(&real_input);
-    d12.parse();
-    debug!("{:?}", d12);
-    d12.find_all_islands();
-    debug!("{:?}", d12);
-    let score = d12.find_total_score_p1();
-    info!("Score is {}", score);
-    let score = d12.find_total_score_p2();
-    info!("Score is {}", score);
+    run_standalone::<Day12Solution>();
 }
@@ -1,6 +1,5 @@
-use aoc2024::{AocHelper, RequestedAocInputType};
-use regex::Regex;
-use tracing::{error, info};
+use aoc2024::{parse::Span, runner::run_standalone, solution::Solution, AocResult};
+use tracing::error;
 
 type Button = (i64, i64);
 type Point = (i64, i64);
@@ -18,11 +17,6 @@ struct Day13 {
     problems: Vec<SysEq>,
 }
 
-// r"Button A: X+17, Y+86"
-
-const BUTTON_REGEX: &str = r"Button [A|B]: X(.*), Y(.*)";
-const TARGET_REGEX: &str = r"Prize: X=(\d+), Y=(\d+)";
-
 const FLOAT_ISSUE_LIMIT: f64 = 0.00000000000005;
 const P2_OFFSET: i64 = 10000000000000;
 
@@ -88,47 +82,17 @@ impl Day13 {
         }
     }
 
-    pub fn parse(&mut self, offset: i64) {
-        let button_regex = Regex::new(BUTTON_REGEX).unwrap();
-        let target_regex = Regex::new(TARGET_REGEX).unwrap();
-        let lv: Vec<String> = self.raw.lines().map(|s| s.to_string()).collect();
-        lv.chunks(4).for_each(|chunk| {
-            // debug!("Chunk: {:?}", chunk);
-            let a_match = button_regex.captures(&chunk[0]).unwrap();
-            let b_match = button_regex.captures(&chunk[1]).unwrap();
-            let target_match = target_regex.captures(&chunk[2]).unwrap();
-
-            // debug!("A {:?} B {:?} Target: {:?}", a_match, b_match, target_match);
-            let x = a_match.get(1).unwrap().as_str().parse::<i64>().unwrap();
-            let y = a_match.get(2).unwrap().as_str().parse::<i64>().unwrap();
-            let a = (x, y);
-
-            let x = b_match.get(1).unwrap().as_str().parse::<i64>().unwrap();
-            let y = b_match.get(2).unwrap().as_str().parse::<i64>().unwrap();
-            let b = (x, y);
-
-            let x = target_match
-                .get(1)
-                .unwrap()
-                .as_str()
-                .parse::<i64>()
-                .unwrap();
-            let y = target_match
-                .get(2)
-                .unwrap()
-                .as_str()
-                .parse::<i64>()
-                .unwrap();
-            let target = (x + offset, y + offset);
-
-            let s = SysEq {
-                a: a,
-                b: b,
-                target: target,
-            };
-
-            self.problems.push(s);
-        });
+    pub fn parse(&mut self, offset: i64) -> AocResult<()> {
+        // Button A: X+94, Y+34 / Button B: X+22, Y+67 / Prize: X=8400, Y=5400
+        for machine in Span::new(&self.raw).sections() {
+            let [ax, ay, bx, by, x, y] = machine.ints_n()?;
+            self.problems.push(SysEq {
+                a: (ax, ay),
+                b: (bx, by),
+                target: (x + offset, y + offset),
+            });
+        }
+        Ok(())
     }
 
     pub fn get_score(&self, is_b: bool) -> i64 {
@@ -141,32 +105,39 @@ impl Day13 {
     }
 }
 
+#[derive(Default)]
+pub struct Day13Solution {
+    d13: Option<Day13>,
+    d13_offset: Option<Day13>,
+}
+
+impl Solution for Day13Solution {
+    fn day(&self) -> u32 {
+        13
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let raw = input.to_string();
+        let mut d13 = Day13::new(&raw);
+        d13.parse(0)?;
+        let mut d13_offset = Day13::new(&raw);
+        d13_offset.parse(P2_OFFSET)?;
+        self.d13 = Some(d13);
+        self.d13_offset = Some(d13_offset);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d13 = self.d13.as_ref()?;
+        Some(d13.get_score(false).to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d13 = self.d13_offset.as_ref()?;
+        Some(d13.get_score(true).to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(13, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d13 = Day13::new(&test_input);
-    d13.parse(0);
-    let ans = d13.get_score(false);
-    info!("Score: {ans}");
-
-    let mut d13 = Day13::new(&real_input);
-    d13.parse(0);
-    let ans = d13.get_score(false);
-    info!("Score: {ans}");
-
-    let mut d13 = Day13::new(&test_input);
-    d13.parse(P2_OFFSET);
-    let ans = d13.get_score(true);
-    info!("Score: {ans}");
-
-    let mut d13 = Day13::new(&real_input);
-    d13.parse(P2_OFFSET);
-    let ans = d13.get_score(true);
-    info!("Score: {ans}");
+    run_standalone::<Day13Solution>();
 }
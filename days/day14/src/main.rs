@@ -1,9 +1,10 @@
-use std::collections::BTreeMap;
-
-use aoc2024::{map_vec_extend::append_to_mapping, AocHelper, RequestedAocInputType};
+use aoc2024::{
+    multimap::BTreeMultiMap, parse_token, runner::run_standalone, solution::Solution,
+    AocHelperError, AocResult, RequestedAocInputType,
+};
 use rayon::iter::IntoParallelRefIterator;
 use regex::Regex;
-use tracing::{debug, info};
+use tracing::{debug, info, trace};
 
 use rayon::prelude::*;
 
@@ -34,7 +35,7 @@ fn display_grid(p: &[Point], bounds: Point) {
         matrix.push(rowstr);
     }
 
-    println!("{}", matrix.join("\n"));
+    trace!("{}", matrix.join("\n"));
 }
 
 fn debug_point_on_grid(p: Point, bounds: Point) {
@@ -130,14 +131,20 @@ impl Day14 {
         }
     }
 
-    pub fn parse(&mut self) {
+    pub fn parse(&mut self) -> AocResult<()> {
         let reg = Regex::new(PV_REGEX).unwrap();
-        self.raw.lines().for_each(|line| {
-            let caps = reg.captures(line).unwrap();
-            let p0: i32 = caps[1].parse::<i32>().unwrap();
-            let p1: i32 = caps[2].parse::<i32>().unwrap();
-            let v0: i32 = caps[3].parse::<i32>().unwrap();
-            let v1: i32 = caps[4].parse::<i32>().unwrap();
+        for (idx, line) in self.raw.lines().enumerate() {
+            let Some(caps) = reg.captures(line) else {
+                return Err(AocHelperError::parse_error(
+                    idx + 1,
+                    1,
+                    "expected `p=x,y v=dx,dy`",
+                ));
+            };
+            let p0: i32 = parse_token(idx, line, &caps[1])?;
+            let p1: i32 = parse_token(idx, line, &caps[2])?;
+            let v0: i32 = parse_token(idx, line, &caps[3])?;
+            let v1: i32 = parse_token(idx, line, &caps[4])?;
 
             debug!("{p0}, {p1} -> {v0}, {v1}");
             let r = Robot {
@@ -147,7 +154,8 @@ impl Day14 {
             };
 
             self.robots.push(r);
-        });
+        }
+        Ok(())
     }
 
     pub fn get_score(&mut self, steps: usize) -> i32 {
@@ -196,39 +204,37 @@ impl Day14 {
         score
     }
 
-    pub fn step_all_and_display(&mut self) {
-        let mut step = 0;
-        loop {
-            step += 1;
+    /// Steps every robot until one row or column holds more than 20 of them
+    /// (the tree), returning that step. Positions repeat after rows * cols
+    /// steps, so give up there.
+    pub fn step_all_and_display(&mut self) -> Option<usize> {
+        for step in 1..=(self.bounds.0 * self.bounds.1) as usize {
             self.robots.par_iter_mut().for_each(|robot| {
                 robot.step_one(self.bounds);
             });
 
             let robs: Vec<Point> = self.robots.iter().map(|robot| robot.current_loc).collect();
-            let mut line_map: BTreeMap<i32, Vec<Point>> = BTreeMap::new();
+            let line_map: BTreeMultiMap<i32, Vec<Point>> =
+                robs.iter().map(|point| (point.0, *point)).collect();
 
-            robs.iter().for_each(|point| {
-                append_to_mapping(&mut line_map, point.0, *point);
-            });
-
-            if line_map.iter().any(|mp| mp.1.len() > 20) {
+            if line_map.iter().any(|(_, line)| line.len() > 20) {
                 info!("Step: {step}");
                 display_grid(&robs, self.bounds);
+                return Some(step);
             }
 
-            let mut line_map: BTreeMap<i32, Vec<Point>> = BTreeMap::new();
+            let line_map: BTreeMultiMap<i32, Vec<Point>> =
+                robs.iter().map(|point| (point.1, *point)).collect();
 
-            robs.iter().for_each(|point| {
-                append_to_mapping(&mut line_map, point.1, *point);
-            });
-
-            if line_map.iter().any(|mp| mp.1.len() > 20) {
+            if line_map.iter().any(|(_, line)| line.len() > 20) {
                 info!("Step: {step}");
                 display_grid(&robs, self.bounds);
+                return Some(step);
             }
 
             // sleep(Duration::from_millis(100));
         }
+        None
     }
 
     pub fn find_cycles(&self) {
@@ -243,36 +249,43 @@ impl Day14 {
     }
 }
 
+#[derive(Default)]
+pub struct Day14Solution {
+    d14: Option<Day14>,
+    bounds: Point,
+}
+
+impl Solution for Day14Solution {
+    fn day(&self) -> u32 {
+        14
+    }
+
+    fn configure(&mut self, input_type: &RequestedAocInputType) {
+        self.bounds = if input_type.is_example() {
+            (7, 11)
+        } else {
+            (103, 101)
+        };
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d14 = Day14::new(&input.to_string(), self.bounds.0, self.bounds.1);
+        d14.parse()?;
+        self.d14 = Some(d14);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d14 = self.d14.as_mut()?;
+        Some(d14.get_score(100).to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d14 = self.d14.as_mut()?;
+        d14.step_all_and_display().map(|step| step.to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(14, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d14 = Day14::new(&test_input, 7, 11);
-    d14.parse();
-    debug!("{:?}", d14);
-    // d14.robots[10].location_after_steps(5, d14.bounds);
-    let ans = d14.get_score(100);
-    info!("Score: {ans}");
-
-    let mut d14 = Day14::new(&real_input, 103, 101);
-    d14.parse();
-    debug!("{:?}", d14);
-    // d14.robots[10].location_after_steps(5, d14.bounds);
-    let ans = d14.get_score(100);
-    info!("Score: {ans}");
-
-    let mut d14 = Day14::new(&real_input, 103, 101);
-    d14.parse();
-    d14.step_all_and_display();
-    // d14.find_cycles();
-
-    // let x: i32 = -18;
-    // let xt: i32 = x.rem_euclid(7 as i32);
-
-    // debug!("{xt}" )
+    run_standalone::<Day14Solution>();
 }
@@ -1,15 +1,43 @@
-use std::collections::BTreeMap;
+use aoc2024::{runner::run_standalone, solution::Solution, AocResult};
 
-use p2::p2;
-
-// type Point = (i32, i32);
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-struct Point(i32, i32);
-type BoundedSet = BTreeMap<usize, Vec<Point>>;
 mod p1;
 mod p2;
 
+#[derive(Default)]
+pub struct Day15Solution {
+    d15: Option<p1::Day15>,
+    d15b: Option<p2::Day15>,
+}
+
+impl Solution for Day15Solution {
+    fn day(&self) -> u32 {
+        15
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let raw = input.to_string();
+        let mut d15 = p1::Day15::new(&raw);
+        d15.parse()?;
+        let mut d15b = p2::Day15::new(&raw);
+        d15b.parse()?;
+        self.d15 = Some(d15);
+        self.d15b = Some(d15b);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d15 = self.d15.as_mut()?;
+        d15.part1_walk();
+        Some(d15.part1_get_score().to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d15 = self.d15b.as_mut()?;
+        d15.walk();
+        Some(d15.get_score().to_string())
+    }
+}
+
 fn main() {
-    // p1();
-    p2();
+    run_standalone::<Day15Solution>();
 }
@@ -1,18 +1,16 @@
-use std::{
-    collections::{BTreeMap, BTreeSet},
-    ops::Add,
-};
+use std::collections::{BTreeMap, BTreeSet};
 
-use aoc2024::{AocHelper, RequestedAocInputType};
-use tracing::{debug, info};
+use aoc2024::{
+    geom::{Direction, Point},
+    parse::Span,
+    AocResult,
+};
+use tracing::{debug, info, trace};
 
-// type Point = (i32, i32);
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-struct Point(i32, i32);
 type BoundedSet = BTreeMap<usize, Vec<Point>>;
 
 #[derive(Debug, Clone)]
-struct Day15 {
+pub struct Day15 {
     raw: String,
     box_starts: BTreeSet<Point>,
     boxes: BTreeSet<Point>,
@@ -20,33 +18,7 @@ struct Day15 {
     robot_loc: Point,
     bounds: Point,
     walls: BTreeSet<Point>,
-    actions: Vec<MoveDir>,
-}
-
-#[derive(Debug, Clone)]
-enum MoveDir {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl MoveDir {
-    pub fn get_delta(&self) -> Point {
-        match self {
-            MoveDir::Up => Point(-1, 0),
-            MoveDir::Down => Point(1, 0),
-            MoveDir::Left => Point(0, -1),
-            MoveDir::Right => Point(0, 1),
-        }
-    }
-}
-
-impl Add for Point {
-    type Output = Point;
-    fn add(self, rhs: Self) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
-    }
+    actions: Vec<Direction>,
 }
 
 impl Day15 {
@@ -55,91 +27,76 @@ impl Day15 {
             raw: s.clone(),
             box_starts: BTreeSet::new(),
             boxes: BTreeSet::new(),
-            robot_start: Point(0, 0),
-            robot_loc: Point(0, 0),
-            bounds: Point(0, 0),
+            robot_start: Point::ORIGIN,
+            robot_loc: Point::ORIGIN,
+            bounds: Point::ORIGIN,
             walls: BTreeSet::new(),
             actions: Vec::new(),
         }
     }
 
-    pub fn parse(&mut self) {
-        let mut sp = self.raw.split("\n\n");
-        let mut mr: usize = 0;
-        let mut mc: usize = 0;
-        for (row, line) in sp.next().unwrap().lines().enumerate() {
-            debug!("Input line {}", line);
-            if line.len() == 0 {
-                break;
-            }
-            for (col, char) in line.chars().enumerate() {
-                let point = Point(row as i32, col as i32);
-                match char {
-                    '#' => {
-                        self.walls.insert(point);
-                    }
-                    '@' => {
-                        self.robot_start = point.clone();
-                        self.robot_loc = point.clone();
-                    }
-                    'O' => {
-                        self.box_starts.insert(point.clone());
-                        self.boxes.insert(point);
-                    }
-                    _ => {}
+    pub fn parse(&mut self) -> AocResult<()> {
+        let [map, moves] = Span::new(&self.raw).sections_n()?;
+        let grid = map.grid(|c| c)?;
+        for ((row, col), char) in grid.iter() {
+            let point = Point::new(row as i32, col as i32);
+            match char {
+                '#' => {
+                    self.walls.insert(point);
+                }
+                '@' => {
+                    self.robot_start = point;
+                    self.robot_loc = point;
                 }
-                mc = col;
+                'O' => {
+                    self.box_starts.insert(point);
+                    self.boxes.insert(point);
+                }
+                _ => {}
             }
-            mr = row;
         }
-        mc += 1;
-        mr += 1;
-        self.bounds = Point(mr as i32, mc as i32);
+        self.bounds = Point::new(grid.height() as i32, grid.width() as i32);
 
-        for dir in sp.next().unwrap().chars() {
-            match dir {
-                '^' => self.actions.push(MoveDir::Up),
-                '<' => self.actions.push(MoveDir::Left),
-                '>' => self.actions.push(MoveDir::Right),
-                'v' => self.actions.push(MoveDir::Down),
-                _ => {}
+        for dir in moves.as_str().chars() {
+            if let Some(dir) = Direction::from_char(dir) {
+                self.actions.push(dir);
             }
         }
+        Ok(())
     }
 
     fn render(&self) {
         let mut mat: Vec<String> = Vec::new();
-        for r in 0..self.bounds.0 {
+        for r in 0..self.bounds.row {
             let mut row: String = String::from("");
-            for c in 0..self.bounds.1 {
+            for c in 0..self.bounds.col {
                 let mut cout = '.';
-                if self.robot_loc == Point(r, c) {
+                if self.robot_loc == Point::new(r, c) {
                     cout = '@';
-                } else if self.walls.contains(&Point(r, c)) {
+                } else if self.walls.contains(&Point::new(r, c)) {
                     cout = '#';
-                } else if self.boxes.contains(&Point(r, c)) {
+                } else if self.boxes.contains(&Point::new(r, c)) {
                     cout = 'O';
                 }
                 row.push(cout);
             }
             mat.push(row);
         }
-        let out = mat.join("\n");
-        println!("{}", out);
+        trace!("{}", mat.join("\n"));
     }
 
     pub fn part1_walk(&mut self) {
         for act in &self.actions {
-            let delta = act.get_delta();
+            let delta: Point = act.delta();
             info!(
                 "Robot starting at {:?} with move {:?}-{:?}",
                 self.robot_loc, act, delta
             );
             let mut boi: Vec<Point> = Vec::new();
-            let mut target = self.robot_loc.clone() + delta.clone();
+            let mut target = self.robot_loc + delta;
             while self.boxes.contains(&target) {
-                boi.push(target.clone());
-                target = target + delta.clone();
+                boi.push(target);
+                target += delta;
             }
 
             if self.walls.contains(&target) {
@@ -151,11 +108,11 @@ impl Day15 {
             } else {
                 debug!("No wall, shifting boxes and moving robot");
                 if boi.len() > 0 {
-                    let first = boi[0].clone();
+                    let first = boi[0];
                     self.boxes.remove(&first);
                     self.boxes.insert(target);
                 }
-                self.robot_loc = self.robot_loc.clone() + delta;
+                self.robot_loc += delta;
             }
             self.render();
         }
@@ -163,35 +120,9 @@ impl Day15 {
 
     pub fn part1_get_score(&self) -> usize {
         self.boxes.iter().fold(0, |acc, bx| {
-            let row: usize = bx.0 as usize;
-            let col: usize = bx.1 as usize;
+            let row: usize = bx.row as usize;
+            let col: usize = bx.col as usize;
             acc + (100 * row + col)
         })
     }
 }
-
-pub fn p1() {
-    let aoc: AocHelper = AocHelper::new(15, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d15 = Day15::new(&test_input);
-    d15.parse();
-    debug!("{:?}", d15);
-    d15.part1_walk();
-    debug!("{:?}", d15);
-    let ans = d15.part1_get_score();
-    info!("Score: {ans}");
-
-    let mut d15 = Day15::new(&real_input);
-    d15.parse();
-    debug!("{:?}", d15);
-    d15.part1_walk();
-    debug!("{:?}", d15);
-    let ans = d15.part1_get_score();
-    info!("Score: {ans}");
-}
@@ -1,32 +1,19 @@
-use std::{
-    cell::RefCell,
-    collections::BTreeSet,
-    fmt::Display,
-    fs::{File, OpenOptions},
-    io::Write,
-    ops::Add,
-    rc::Rc,
-};
+use std::{collections::BTreeSet, fmt::Display, ops::Add};
 
-use aoc2024::{AocHelper, RequestedAocInputType};
-use tracing::{debug, error, info};
-
-// type Point = (i32, i32);
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-struct Point(i32, i32);
+use aoc2024::{geom::Point, AocHelperError, AocResult};
+use tracing::{debug, error, info, trace};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 struct BoxPiece(Point, char);
 
 #[derive(Debug, Clone)]
-struct Day15 {
+pub struct Day15 {
     raw: String,
     boxes: BTreeSet<BoxPiece>,
     robot_loc: Point,
     bounds: Point,
     walls: BTreeSet<Point>,
     actions: Vec<MoveDir>,
-    render_file: Rc<RefCell<File>>,
 }
 
 enum Contents {
@@ -48,11 +35,11 @@ enum MoveDir {
 impl MoveDir {
     pub fn get_delta(&self) -> Point {
         match self {
-            MoveDir::Up => Point(-1, 0),
-            MoveDir::Down => Point(1, 0),
-            MoveDir::Left => Point(0, -1),
-            MoveDir::Right => Point(0, 1),
-            MoveDir::None => Point(0, 0),
+            MoveDir::Up => Point::new(-1, 0),
+            MoveDir::Down => Point::new(1, 0),
+            MoveDir::Left => Point::new(0, -1),
+            MoveDir::Right => Point::new(0, 1),
+            MoveDir::None => Point::ORIGIN,
         }
     }
 }
@@ -70,13 +57,6 @@ impl Display for MoveDir {
     }
 }
 
-impl Add for Point {
-    type Output = Point;
-    fn add(self, rhs: Self) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
 impl Add for BoxPiece {
     type Output = BoxPiece;
 
@@ -104,41 +84,32 @@ impl Add<char> for BoxPiece {
 }
 
 impl Day15 {
-    pub fn new(s: &String, out: &String) -> Self {
-        let file: File = OpenOptions::new()
-            .write(true)
-            .append(true)
-            .create(true)
-            .open("day15b.txt")
-            .unwrap();
-
-        let frc = Rc::new(RefCell::new(file));
-
+    pub fn new(s: &String) -> Self {
         Self {
             raw: s.clone(),
             boxes: BTreeSet::new(),
-            robot_loc: Point(0, 0),
-            bounds: Point(0, 0),
+            robot_loc: Point::ORIGIN,
+            bounds: Point::ORIGIN,
             walls: BTreeSet::new(),
             actions: Vec::new(),
-            render_file: frc,
         }
     }
 
-    pub fn parse(&mut self) {
+    pub fn parse(&mut self) -> AocResult<()> {
         let mut expanded = self.raw.clone();
         expanded = expanded.replace("#", "##");
         expanded = expanded.replace(".", "..");
         expanded = expanded.replace("O", "[]");
         expanded = expanded.replace("@", "@.");
         let mut sp = expanded.split("\n\n");
-        info!("Before:");
-        println!("{}", self.raw);
-        info!("After expansion:");
-        println!("{}", expanded);
+        debug!("Before:\n{}", self.raw);
+        debug!("After expansion:\n{}", expanded);
         let mut mr: usize = 0;
         let mut mc: usize = 0;
-        for (row, line) in sp.next().unwrap().lines().enumerate() {
+        let Some(map) = sp.next() else {
+            return Err(AocHelperError::parse_error(1, 1, "empty input"));
+        };
+        for (row, line) in map.lines().enumerate() {
             debug!("Input line {}", line);
             if line.len() == 0 {
                 break;
@@ -146,7 +117,7 @@ impl Day15 {
             let vc: Vec<char> = line.chars().collect();
             for (col, char) in vc.iter().enumerate() {
                 // let col: i32 = col_offset as i32 * 2;
-                let point = Point(row as i32, col as i32);
+                let point = Point::new(row as i32, col as i32);
                 match char {
                     '#' => {
                         self.walls.insert(point.clone());
@@ -168,9 +139,16 @@ impl Day15 {
         }
         mc += 1;
         mr += 1;
-        self.bounds = Point(mr as i32, mc as i32);
-
-        for dir in sp.next().unwrap().chars() {
+        self.bounds = Point::new(mr as i32, mc as i32);
+
+        let Some(moves) = sp.next() else {
+            return Err(AocHelperError::parse_error(
+                mr + 1,
+                1,
+                "expected a blank line between the map and the moves",
+            ));
+        };
+        for dir in moves.chars() {
             match dir {
                 '^' => self.actions.push(MoveDir::Up),
                 '<' => self.actions.push(MoveDir::Left),
@@ -179,17 +157,17 @@ impl Day15 {
                 _ => {}
             }
         }
+        Ok(())
     }
 
-    fn render(&mut self, last_move: &MoveDir, idx: usize) {
+    fn render(&self, last_move: &MoveDir, idx: usize) {
         let mut mat: Vec<String> = Vec::new();
-        let f_out = &mut self.render_file;
         mat.push(format!("Step {} Direction: {}", idx, last_move));
-        for r in 0..self.bounds.0 {
+        for r in 0..self.bounds.row {
             let mut row: String = String::from("");
-            for c in 0..self.bounds.1 {
+            for c in 0..self.bounds.col {
                 let mut cout = '.';
-                let this_point = Point(r, c);
+                let this_point = Point::new(r, c);
                 let lcont = self.boxes.contains(&BoxPiece(this_point.clone(), 'l'));
                 let rcont = self.boxes.contains(&BoxPiece(this_point.clone(), 'r'));
 
@@ -210,9 +188,7 @@ impl Day15 {
             }
             mat.push(row);
         }
-        let out = mat.join("\n");
-        // println!("{}", out.clone());
-        write!(f_out.borrow_mut(), "{}", out + "\n");
+        trace!("{}", mat.join("\n"));
     }
 
     fn get_targets(&self, start: BoxPiece, dir: &MoveDir) -> Option<BTreeSet<BoxPiece>> {
@@ -280,10 +256,10 @@ impl Day15 {
             let imm_bp = BoxPiece(imm_target, orig_dir);
             if lcont {
                 lb_targets = self.get_targets(imm_bp + 'l', dir);
-                rb_targets = self.get_targets(imm_bp + 'r' + Point(0, 1), dir);
+                rb_targets = self.get_targets(imm_bp + 'r' + Point::new(0, 1), dir);
             } else if rcont {
                 rb_targets = self.get_targets(imm_bp + 'r', dir);
-                lb_targets = self.get_targets(imm_bp + 'l' + Point(0, -1), dir);
+                lb_targets = self.get_targets(imm_bp + 'l' + Point::new(0, -1), dir);
             } else {
                 return Some(out);
             }
@@ -345,33 +321,7 @@ impl Day15 {
                 return acc;
             }
 
-            acc + (100 * b.0 .0) + b.0 .1
+            acc + (100 * b.0.row) + b.0.col
         })
     }
 }
-
-pub fn p2() {
-    let aoc: AocHelper = AocHelper::new(15, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d15 = Day15::new(&test_input, &"day15b.txt".to_string());
-    d15.parse();
-    debug!("{:?}", d15);
-    d15.render(&MoveDir::None, 0);
-    d15.walk();
-    let ans = d15.get_score();
-    info!("Score: {ans}");
-
-    let mut d15 = Day15::new(&real_input, &"day15b.txt".to_string());
-    d15.parse();
-    debug!("{:?}", d15);
-    d15.render(&MoveDir::None, 0);
-    d15.walk();
-    let ans = d15.get_score();
-    info!("Score: {ans}");
-}
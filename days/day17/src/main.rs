@@ -1,6 +1,9 @@
 use std::collections::HashSet;
 
-use aoc2024::{AocHelper, RequestedAocInputType};
+use aoc2024::{
+    parse_token, runner::run_standalone, solution::Solution, AocHelperError, AocResult,
+    RequestedAocInputType,
+};
 use rayon::prelude::*;
 use tracing::{debug, error, info};
 
@@ -170,29 +173,35 @@ impl Day17 {
         }
     }
 
-    pub fn parse(&mut self) {
+    pub fn parse(&mut self) -> AocResult<()> {
         for (idx, line) in self.raw.lines().enumerate() {
             if idx < 3 {
-                self.reg_start_state[idx] = line
-                    .split(": ")
-                    .last()
-                    .unwrap()
-                    .parse::<RegisterInt>()
-                    .unwrap();
+                let Some((_, value)) = line.split_once(": ") else {
+                    return Err(AocHelperError::parse_error(
+                        idx + 1,
+                        1,
+                        "expected `Register X: value`",
+                    ));
+                };
+                self.reg_start_state[idx] = parse_token(idx, line, value)?;
                 continue;
             }
             if idx == 3 {
                 continue;
             }
-            line.split("Program: ")
-                .last()
-                .unwrap()
-                .split(",")
-                .for_each(|opc| {
-                    debug!("Testing {opc}");
-                    self.program_asm.push(opc.parse::<u8>().unwrap())
-                });
+            let Some(program) = line.strip_prefix("Program: ") else {
+                return Err(AocHelperError::parse_error(
+                    idx + 1,
+                    1,
+                    "expected `Program: ...`",
+                ));
+            };
+            for opc in program.split(",") {
+                debug!("Testing {opc}");
+                self.program_asm.push(parse_token(idx, line, opc)?);
+            }
         }
+        Ok(())
     }
 
     pub fn run_machine(&self) -> String {
@@ -204,7 +213,7 @@ impl Day17 {
         };
 
         machine.run();
-        println!("{:?}", machine.output);
+        debug!("{:?}", machine.output);
         let output: String = machine.output.iter().map(|o| o.to_string() + ",").collect();
 
         output
@@ -245,7 +254,7 @@ fn brute_force() {
         });
 }
 
-fn reverse_out() {
+fn reverse_out() -> Option<u64> {
     let mut valids: HashSet<u64> = HashSet::new();
     valids.insert(0);
     for target in brute_ans.iter().rev() {
@@ -267,34 +276,45 @@ fn reverse_out() {
     }
     info!("valids: {:?}", valids);
     info!("{:?}", valids.iter().min());
+    valids.iter().min().copied()
+}
+
+#[derive(Default)]
+pub struct Day17Solution {
+    d17: Option<Day17>,
+    is_example: bool,
+}
+
+impl Solution for Day17Solution {
+    fn day(&self) -> u32 {
+        17
+    }
+
+    fn configure(&mut self, input_type: &RequestedAocInputType) {
+        self.is_example = input_type.is_example();
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d17 = Day17::new(&input.to_string());
+        d17.parse()?;
+        self.d17 = Some(d17);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d17 = self.d17.as_ref()?;
+        Some(d17.run_machine().trim_end_matches(',').to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        // reverse_out is hand-derived from the real program, see brute_ans
+        if self.is_example {
+            return None;
+        }
+        reverse_out().map(|a| a.to_string())
+    }
 }
 
 fn main() {
-    let aoc: AocHelper = AocHelper::new(17, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    // let mut d17 = Day17::new(&test_input);
-    // d17.parse();
-    // debug!("{:?}", d17);
-    // let ans = d17.run_machine();
-    // info!("Ans: {ans}");
-
-    // let mut d17 = Day17::new(&real_input);
-    // d17.parse();
-    // debug!("{:?}", d17);
-    // let ans = d17.run_machine();
-    // info!("Ans: {ans}");
-
-    info!("wah");
-    reverse_out();
-    info!("wtf");
-    // brute_force();
-    // d17.reg_start_state[0] = 7552015;
-    // let ans = d17.run_machine();
-    // info!("Ans: {ans}");
+    run_standalone::<Day17Solution>();
 }
@@ -1,10 +1,16 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use aoc2024::{
-    graph::{Graph, SCORE_MAX},
-    AocHelper, RequestedAocInputType,
+    components::DisjointSet,
+    graph::Neighbors,
+    grid::{Grid, Pos},
+    parse_token,
+    runner::run_standalone,
+    search,
+    solution::Solution,
+    AocHelperError, AocResult, RequestedAocInputType,
 };
-use tracing::{debug, info};
+use tracing::{info, trace};
 
 type Point = (i32, i32);
 
@@ -17,11 +23,9 @@ struct Day18 {
     walls_list: Vec<Point>,
     walls: HashSet<Point>,
     bounds: Point,
-    graph: Graph<Point>,
+    grid: Grid<bool>,
 }
 
-const NEIGHBOR_OFFSETS: [Point; 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
-
 impl Day18 {
     pub fn new(s: &String, rows: i32, cols: i32) -> Self {
         Self {
@@ -29,156 +33,157 @@ impl Day18 {
             walls: HashSet::new(),
             walls_list: Vec::new(),
             bounds: (rows + 1, cols + 1),
-            graph: Graph::new(),
+            grid: Grid::new(0, 0, true),
         }
     }
 
-    pub fn parse(&mut self) {
-        for line in self.raw.lines().into_iter() {
-            let v: Vec<i32> = line.split(',').map(|x| x.parse::<i32>().unwrap()).collect();
+    pub fn parse(&mut self) -> AocResult<()> {
+        for (idx, line) in self.raw.lines().enumerate() {
+            let Some((x, y)) = line.split_once(',') else {
+                return Err(AocHelperError::parse_error(idx + 1, 1, "expected `x,y`"));
+            };
+            let x: i32 = parse_token(idx, line, x)?;
+            let y: i32 = parse_token(idx, line, y)?;
             // everything is in row,col (like everything else this year)
-            // self.walls.insert((v[1],v[0]));
-            self.walls_list.push((v[1], v[0]));
+            // self.walls.insert((y, x));
+            self.walls_list.push((y, x));
 
             // if self.walls.len() >= point_count {
             //     return;
             // }
         }
+        Ok(())
     }
 
-    pub fn make_graph(&mut self, point_count: usize) {
-        self.graph = Graph::new();
-        let mut ww_list = self.walls_list.clone();
-        ww_list.truncate(point_count);
-        let working_walls: HashSet<Point> = ww_list.iter().map(|w| *w).collect();
-        self.walls = working_walls.clone();
-        for row in 0..self.bounds.0 {
-            for col in 0..self.bounds.1 {
-                let p = (row, col);
-                if working_walls.contains(&p) {
-                    continue;
-                }
-
-                self.graph.add_point(p);
-            }
-        }
-
-        let non_walls = self.graph.points.clone();
-        for non_wall in non_walls {
-            NEIGHBOR_OFFSETS
-                .iter()
-                .filter_map(|offset| {
-                    let np = (non_wall.0 + offset.0, non_wall.1 + offset.1);
-                    let inbounds =
-                        np.0 >= 0 && np.0 < self.bounds.0 && np.1 >= 0 && np.1 < self.bounds.1;
-                    if inbounds && !self.walls.contains(&np) {
-                        return Some(np);
-                    }
-                    None
-                })
-                .for_each(|valid| {
-                    self.graph.add_edge(&non_wall, &valid, 1);
-                    // self.graph.add_edge(&valid, &non_wall, 1);
-                });
+    /// Open cells are `true`, with the first `point_count` bytes fallen.
+    pub fn make_grid(&mut self, point_count: usize) {
+        let (rows, cols) = (self.bounds.0 as usize, self.bounds.1 as usize);
+        self.grid = Grid::new(cols, rows, true);
+        self.walls = self.walls_list.iter().take(point_count).copied().collect();
+        for wall in self.walls.iter() {
+            self.grid.set((wall.0 as usize, wall.1 as usize), false);
         }
     }
 
-    pub fn print_grid(&self, path: &Vec<Point>) {
+    pub fn print_grid(&self, path: &[Pos]) {
         for row in 0..self.bounds.0 {
             let mut rv: String = String::new();
             for col in 0..self.bounds.1 {
                 if self.walls.contains(&(row, col)) {
                     rv.push('#');
-                } else if path.contains(&(row, col)) {
+                } else if path.contains(&(row as usize, col as usize)) {
                     rv.push('O');
                 } else {
                     rv.push('.');
                 }
             }
-            println!("{}", rv);
+            trace!("{}", rv);
         }
     }
 
-    pub fn get_shortest_path(&self) -> i32 {
-        let actual_corner = (self.bounds.0 - 1, self.bounds.1 - 1);
-        let (score, path) = self.graph.shortest_path_len(&(0, 0), &actual_corner);
+    fn shortest_path(&self) -> Option<(i32, Vec<Pos>)> {
+        let corner = (self.grid.height() - 1, self.grid.width() - 1);
+        let to_corner = |p: &Pos| (corner.0 - p.0 + corner.1 - p.1) as i32;
+        search::astar(&self.grid, [(0, 0)], |p| *p == corner, to_corner)
+    }
+
+    pub fn get_shortest_path(&self) -> Option<i32> {
+        let (score, path) = self.shortest_path()?;
         // debug!("{:?}", path);
         // self.print_grid(&path);
-        score
+        Some(score)
     }
 
-    pub fn find_impossible_byte(&mut self) -> (i32, i32) {
-        let total = self.walls_list.len();
-        let mut midpoint = total / 2;
-        let mut stepsize = midpoint;
-        let actual_corner = (self.bounds.0 - 1, self.bounds.1 - 1);
-
-        while stepsize > 1 {
-            info!("Trying to find impossible with half = {midpoint} step size = {stepsize}");
-            self.make_graph(midpoint);
-            let (score, _) = self.graph.shortest_path_len(&(0, 0), &actual_corner);
-
-            stepsize = stepsize / 2 - 1;
-            if score != SCORE_MAX {
-                // still good, go higher
-                info!("Score {score} is still good, going up");
-                midpoint = midpoint + stepsize;
-            } else {
-                info!("Score {score} is no good, going down");
-                // stepsize = stepsize / 2;
-                midpoint = midpoint - stepsize;
+    /// Lets every byte fall, then lifts them off again latest first,
+    /// joining up the cells they free, until the corners connect. The byte
+    /// that connected them is the first one that cut them off. `None` if even
+    /// every byte together leaves a way through.
+    pub fn find_impossible_byte(&mut self) -> Option<Point> {
+        self.make_grid(self.walls_list.len());
+        let width = self.grid.width();
+        let index = |p: Pos| p.0 * width + p.1;
+        let corner = index((self.grid.height() - 1, width - 1));
+        let mut sets = DisjointSet::new(width * self.grid.height());
+        for pos in self.grid.find_all(&true) {
+            for next in self.grid.neighbors(&pos) {
+                sets.union(index(pos), index(next));
             }
         }
+        if sets.same(0, corner) {
+            return None;
+        }
 
-        for idx in midpoint - 3..midpoint + 3 {
-            self.make_graph(idx);
-            let (score, _) = self.graph.shortest_path_len(&(0, 0), &actual_corner);
-            info!(
-                "Score: {score} idx: {idx} point: {:?}",
-                self.walls_list[idx]
-            );
+        // a byte landing twice only frees its cell when the first one lifts
+        let mut first_fall: HashMap<Point, usize> = HashMap::new();
+        for (idx, wall) in self.walls_list.iter().enumerate() {
+            first_fall.entry(*wall).or_insert(idx);
+        }
+        for (idx, wall) in self.walls_list.iter().enumerate().rev() {
+            if first_fall[wall] != idx {
+                continue;
+            }
+            let pos = (wall.0 as usize, wall.1 as usize);
+            self.grid.set(pos, true);
+            self.walls.remove(wall);
+            for next in self.grid.neighbors(&pos) {
+                sets.union(index(pos), index(next));
+            }
+            if sets.same(0, corner) {
+                info!("Byte {idx} at {:?} is the first to block the way", wall);
+                return Some(*wall);
+            }
         }
+        None
+    }
+}
+
+#[derive(Default)]
+pub struct Day18Solution {
+    d18: Option<Day18>,
+    bounds: Point,
+    byte_count: usize,
+}
 
-        self.walls_list[midpoint - 1]
+impl Solution for Day18Solution {
+    fn day(&self) -> u32 {
+        18
+    }
+
+    fn configure(&mut self, input_type: &RequestedAocInputType) {
+        if input_type.is_example() {
+            self.bounds = (6, 6);
+            self.byte_count = 12;
+        } else {
+            self.bounds = (70, 70);
+            self.byte_count = 1024;
+        }
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d18 = Day18::new(&input.to_string(), self.bounds.0, self.bounds.1);
+        d18.parse()?;
+        self.d18 = Some(d18);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d18 = self.d18.as_mut()?;
+        d18.make_grid(self.byte_count);
+        match d18.get_shortest_path() {
+            Some(score) => Some(score.to_string()),
+            None => Some("no path".to_string()),
+        }
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d18 = self.d18.as_mut()?;
+        match d18.find_impossible_byte() {
+            Some(ans) => Some(format!("{},{}", ans.1, ans.0)),
+            None => Some("never blocked".to_string()),
+        }
     }
 }
 
 fn main() {
-    let aoc: AocHelper = AocHelper::new(18, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d18 = Day18::new(&test_input, 6, 6);
-    d18.parse();
-    debug!("d18: {:?}", d18);
-    d18.make_graph(12);
-    debug!("d18: {:?}", d18);
-    d18.print_grid(&Vec::new());
-    let path_len = d18.get_shortest_path();
-    info!("Path len: {path_len}");
-
-    let mut d18 = Day18::new(&real_input, 70, 70);
-    d18.parse();
-    // debug!("d18: {:?}", d18);
-    d18.make_graph(1024);
-    // debug!("d18: {:?}", d18);
-    d18.print_grid(&Vec::new());
-    let path_len = d18.get_shortest_path();
-    info!("Path len: {path_len}");
-
-    let mut d18 = Day18::new(&test_input, 6, 6);
-    d18.parse();
-    let ans = d18.find_impossible_byte();
-    info!("Ans : {:?}", ans);
-    info!("Answer: {}, {}", ans.1, ans.0);
-
-    let mut d18 = Day18::new(&real_input, 70, 70);
-    d18.parse();
-    let ans = d18.find_impossible_byte();
-    info!("Ans : {:?}", ans);
-    info!("Answer: {}, {}", ans.1, ans.0);
+    run_standalone::<Day18Solution>();
 }
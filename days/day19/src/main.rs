@@ -1,12 +1,14 @@
 use std::collections::BTreeMap;
 
-use aoc2024::{map_vec_extend::append_to_mapping, AocHelper, RequestedAocInputType};
+use aoc2024::{
+    multimap::BTreeMultiMap, runner::run_standalone, solution::Solution, AocHelperError, AocResult,
+};
 use tracing::{debug, error, info};
 
 #[derive(Debug)]
 struct Day19 {
     raw: String,
-    pieces: BTreeMap<char, Vec<String>>,
+    pieces: BTreeMultiMap<char, Vec<String>>,
     targets: Vec<String>,
     checked: BTreeMap<(String, String), bool>,
 }
@@ -15,21 +17,34 @@ impl Day19 {
     pub fn new(s: &String) -> Self {
         Self {
             raw: s.clone(),
-            pieces: BTreeMap::new(),
+            pieces: BTreeMultiMap::new(),
             targets: Vec::new(),
             checked: BTreeMap::new(),
         }
     }
 
-    pub fn parse(&mut self) {
-        let (pieces, targets) = self.raw.split_once("\n\n").unwrap();
-        pieces.split(", ").for_each(|p| {
-            let fc = p.chars().next().unwrap();
-            append_to_mapping(&mut self.pieces, fc, p.to_string());
-        });
+    pub fn parse(&mut self) -> AocResult<()> {
+        let Some((pieces, targets)) = self.raw.split_once("\n\n") else {
+            return Err(AocHelperError::parse_error(
+                2,
+                1,
+                "expected a blank line between the towels and the designs",
+            ));
+        };
+        for p in pieces.split(", ") {
+            let Some(fc) = p.chars().next() else {
+                return Err(AocHelperError::parse_error_in(
+                    &self.raw,
+                    p,
+                    "empty towel pattern",
+                ));
+            };
+            self.pieces.insert(fc, p.to_string());
+        }
         targets.lines().for_each(|line| {
             self.targets.push(line.to_string());
         });
+        Ok(())
     }
 
     pub fn is_possible(
@@ -165,37 +180,34 @@ impl Day19 {
     }
 }
 
+#[derive(Default)]
+pub struct Day19Solution {
+    d19: Option<Day19>,
+}
+
+impl Solution for Day19Solution {
+    fn day(&self) -> u32 {
+        19
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d19 = Day19::new(&input.to_string());
+        d19.parse()?;
+        self.d19 = Some(d19);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d19 = self.d19.as_mut()?;
+        Some(d19.count_possible().to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d19 = self.d19.as_ref()?;
+        Some(d19.count_all_perms().to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(19, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-    // AocHelper::setup_subscriber(Level::INFO);
-
-    let mut d19 = Day19::new(&test_input);
-    d19.parse();
-    debug!("{:?}", d19);
-    let ans = d19.count_possible();
-    info!("Ans: {ans}");
-
-    let mut d19 = Day19::new(&real_input);
-    d19.parse();
-    debug!("{:?}", d19);
-    let ans = d19.count_possible();
-    info!("Ans: {ans}");
-
-    let mut d19 = Day19::new(&test_input);
-    d19.parse();
-    debug!("{:?}", d19);
-    let ans = d19.count_all_perms();
-    info!("Ans: {ans}");
-
-    let mut d19 = Day19::new(&real_input);
-    d19.parse();
-    debug!("{:?}", d19);
-    let ans = d19.count_all_perms();
-    info!("Ans: {ans}");
+    run_standalone::<Day19Solution>();
 }
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use aoc2024::{AocHelper, RequestedAocInputType};
+use aoc2024::{parse::Span, runner::run_standalone, solution::Solution, AocResult};
 use tracing::info;
 
 struct Day2A {
@@ -20,15 +20,11 @@ impl Day2A {
         }
     }
 
-    pub fn parse(&mut self) {
-        let lines = self.raw.lines();
-        lines.for_each(|line| {
-            let ii = line
-                .split_ascii_whitespace()
-                .map(|l| l.parse::<i32>().unwrap());
-            let v: Vec<i32> = Vec::from_iter(ii);
-            self.rows.push(v);
-        });
+    pub fn parse(&mut self) -> AocResult<()> {
+        for line in Span::new(&self.raw).lines() {
+            self.rows.push(line.parse_words()?);
+        }
+        Ok(())
     }
 
     fn get_row_unsafe_index(row: &Vec<i32>) -> Option<usize> {
@@ -142,33 +138,34 @@ impl Day2A {
     }
 }
 
+#[derive(Default)]
+pub struct Day2Solution {
+    d2: Option<Day2A>,
+}
+
+impl Solution for Day2Solution {
+    fn day(&self) -> u32 {
+        2
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d2 = Day2A::new(&input.to_string());
+        d2.parse()?;
+        self.d2 = Some(d2);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d2 = self.d2.as_ref()?;
+        Some(d2.get_safe_rows().to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d2 = self.d2.as_mut()?;
+        Some(d2.forget_it_brute_force_it().to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(2, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d2a_test = Day2A::new(&test_input);
-    d2a_test.parse();
-    info!("Current: {:?}", d2a_test.rows.clone());
-    let safe = d2a_test.get_safe_rows();
-    let semi_safe = d2a_test.get_semi_safe_rows();
-    info!("Safe row count: {}", safe);
-    info!("Semi Safe row count: {}", semi_safe);
-
-    // return;
-    let mut d2a_real = Day2A::new(&real_input);
-    d2a_real.parse();
-    info!("Current: {:?}", d2a_real.rows.clone());
-    let safe = d2a_real.get_safe_rows();
-    info!("Safe row count: {}", safe);
-    let semi_safe = d2a_real.get_semi_safe_rows();
-    info!("Semi Safe row count: {}", semi_safe);
-    let bf = d2a_real.forget_it_brute_force_it();
-    info!("Brute force count: {}", bf);
-
-    d2a_real.what_even();
+    run_standalone::<Day2Solution>();
 }
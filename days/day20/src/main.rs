@@ -1,10 +1,10 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
 
 use aoc2024::{
-    counter::BTreeCounter, map_vec_extend::append_to_mapping, AocHelper,
-    RequestedAocInputType,
+    counter::BTreeCounter, multimap::BTreeMultiMap, runner::run_standalone,
+    search, solution::Solution, AocHelperError, AocResult, RequestedAocInputType,
 };
-use tracing::{debug, info};
+use tracing::{debug, info, trace};
 
 type Point = (i32, i32);
 const NEIGHBOR_OFFSETS: [Point; 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
@@ -38,7 +38,7 @@ impl Day20 {
         }
     }
 
-    pub fn parse(&mut self) {
+    pub fn parse(&mut self) -> AocResult<()> {
         let mut path_set: HashSet<Point> = HashSet::new();
         for (row, line) in self.raw.lines().enumerate() {
             for (col, c) in line.chars().enumerate() {
@@ -63,33 +63,30 @@ impl Day20 {
             }
         }
         let mr = self.raw.lines().count();
-        let mc = self.raw.lines().next().unwrap().chars().count();
+        let Some(first_row) = self.raw.lines().next() else {
+            return Err(AocHelperError::parse_error(1, 1, "empty input"));
+        };
+        let mc = first_row.chars().count();
         self.bounds = (mr as i32, mc as i32);
-        let mut cur: Point = self.start;
-        while cur != self.end {
-            let mut next_found = false;
-            self.path.push(cur);
-            self.path_map.insert(cur, self.path.len() - 1);
-            let neighbors = NEIGHBOR_OFFSETS.map(|ofs| (cur.0 + ofs.0, cur.1 + ofs.1));
-            for n in neighbors {
-                if self.path.contains(&n) {
-                    continue;
-                }
-                if path_set.contains(&n) {
-                    cur = n;
-                    next_found = true;
-                    break;
-                }
-            }
-            if !next_found {
-                panic!(
-                    "Couldn't find a neighbor for {:?}. Checked: {:?}",
-                    cur, neighbors
-                );
-            }
+        let end = self.end;
+        let track = |cur: &Point| {
+            NEIGHBOR_OFFSETS
+                .map(|ofs| add_points(cur, &ofs))
+                .into_iter()
+                .filter(|n| path_set.contains(n))
+        };
+        let Some(path) = search::bfs_to(&track, [self.start], |p| *p == end) else {
+            return Err(AocHelperError::parse_error(
+                self.start.0 as usize + 1,
+                self.start.1 as usize + 1,
+                "the track never reaches E",
+            ));
+        };
+        for (idx, p) in path.iter().enumerate() {
+            self.path_map.insert(*p, idx);
         }
-        self.path.push(cur);
-        self.path_map.insert(cur, self.path.len() - 1);
+        self.path = path;
+        Ok(())
     }
 
     fn check_len(&self, a: Point, b: Point) -> i32 {
@@ -134,22 +131,15 @@ impl Day20 {
     }
 
     pub fn get_min_savings_count_p1(&self, min_score: i32) -> usize {
-        let mut res = 0;
         let counter = self.find_p1_cheats_and_scores();
-        let scores: Vec<&i32> = counter.keys().filter(|k| **k >= min_score).collect();
-
-        for s in scores {
-            let c = counter.get(s).unwrap();
-            res += c;
-            info!("There are {c} cheats that save {s} picoseconds");
-        }
-
+        let res = counter.total_in(min_score..);
+        info!("{res} cheats save at least {min_score} picoseconds");
         res
     }
 
     pub fn find_dynamic_cheats(&self, p: &Point, steps: usize) -> HashMap<(i32, i32), usize> {
         // mapping between endpoint of cheat and cheat len (iter count)
-        let mut res: BTreeMap<Point, Vec<usize>> = BTreeMap::new();
+        let mut res: BTreeMultiMap<Point, Vec<usize>> = BTreeMultiMap::new();
         let mut wall_pieces: HashSet<Point> = HashSet::new();
         wall_pieces.insert(p.clone());
 
@@ -163,7 +153,7 @@ impl Day20 {
                     .collect();
                 for t in targets {
                     if self.path_map.contains_key(&t) {
-                        append_to_mapping(&mut res, t, step);
+                        res.insert(t, step);
                         next_pieces.insert(t);
                     } else if self.walls.contains(&t) {
                         next_pieces.insert(t);
@@ -216,16 +206,9 @@ impl Day20 {
     }
 
     pub fn get_min_savings_count_p2(&self, min_score: i32, steps: usize) -> usize {
-        let mut res = 0;
         let counter = self.find_p2_cheats_and_scores(steps);
-        let scores: Vec<&i32> = counter.keys().filter(|k| **k >= min_score).collect();
-
-        for s in scores {
-            let c = counter.get(s).unwrap();
-            res += c;
-            info!("There are {c} cheats that save {s} picoseconds");
-        }
-
+        let res = counter.total_in(min_score..);
+        info!("{res} cheats save at least {min_score} picoseconds");
         res
     }
 
@@ -246,52 +229,54 @@ impl Day20 {
             out.push('\n');
             out.push_str(&rs);
         }
-        println!("{}", out);
+        trace!("{}", out);
     }
 }
 
-fn main() {
-    let aoc: AocHelper = AocHelper::new(20, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
+#[derive(Default)]
+pub struct Day20Solution {
+    d20: Option<Day20>,
+    p1_min_savings: i32,
+    p2_min_savings: i32,
+    p2_steps: usize,
+}
 
-    // let mut d20 = Day20::new(&test_input);
-    // d20.parse();
-    // debug!("{:?}", d20);
-    // let total_len = d20.check_len(d20.start, d20.end);
-    // info!("{} is total path len", total_len);
-    // d20.find_p1_cheats_and_scores();
-    // let ans = d20.get_min_savings_count_p1(23);
-    // info!("Ans: {ans}");
+impl Solution for Day20Solution {
+    fn day(&self) -> u32 {
+        20
+    }
 
-    // let mut d20 = Day20::new(&real_input);
-    // d20.parse();
-    // debug!("{:?}", d20);
-    // let total_len = d20.check_len(d20.start, d20.end);
-    // info!("{} is total path len", total_len);
-    // d20.find_p1_cheats_and_scores();
-    // let ans = d20.get_min_savings_count_p1(100);
-    // info!("Ans: {ans}");
+    fn configure(&mut self, input_type: &RequestedAocInputType) {
+        if input_type.is_example() {
+            self.p1_min_savings = 23;
+            self.p2_min_savings = 50;
+            self.p2_steps = 21;
+        } else {
+            self.p1_min_savings = 100;
+            self.p2_min_savings = 100;
+            self.p2_steps = 20;
+        }
+    }
 
-    let mut d20 = Day20::new(&test_input);
-    d20.parse();
-    debug!("{:?}", d20);
-    let total_len = d20.check_len(d20.start, d20.end);
-    info!("{} is total path len", total_len);
-    d20.render_board();
-    let ans = d20.get_min_savings_count_p2(50, 21);
-    info!("Ans: {ans}");
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d20 = Day20::new(&input.to_string());
+        d20.parse()?;
+        self.d20 = Some(d20);
+        Ok(())
+    }
 
-    let mut d20 = Day20::new(&real_input);
-    d20.parse();
-    debug!("{:?}", d20);
-    let total_len = d20.check_len(d20.start, d20.end);
-    info!("{} is total path len", total_len);
-    d20.render_board();
-    let ans = d20.get_min_savings_count_p2(100, 20);
-    info!("Ans: {ans}");
+    fn part1(&mut self) -> Option<String> {
+        let d20 = self.d20.as_ref()?;
+        Some(d20.get_min_savings_count_p1(self.p1_min_savings).to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d20 = self.d20.as_ref()?;
+        let ans = d20.get_min_savings_count_p2(self.p2_min_savings, self.p2_steps);
+        Some(ans.to_string())
+    }
+}
+
+fn main() {
+    run_standalone::<Day20Solution>();
 }
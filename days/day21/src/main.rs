@@ -1,8 +1,11 @@
 use std::collections::{HashMap, HashSet};
 
 use aoc2024::{
-    map_vec_extend::append_to_hash_set,
-    AocHelper, RequestedAocInputType,
+    multimap::HashMultiMap,
+    parse_token,
+    runner::run_standalone,
+    solution::Solution,
+    AocHelperError, AocResult,
 };
 use itertools::{repeat_n, Itertools};
 use rayon::prelude::*;
@@ -82,7 +85,7 @@ struct Keypad {
     p_to_k: HashMap<Point, char>,
     k_to_p: HashMap<char, Point>,
     bounds: Point,
-    paths: HashMap<(char, char), HashSet<Vec<Direction>>>,
+    paths: HashMultiMap<(char, char), HashSet<Vec<Direction>>>,
     point_set: HashSet<Point>,
     offlimits: Point,
     string_paths: HashMap<(char, char), Vec<String>>,
@@ -94,7 +97,7 @@ impl Keypad {
             raw: s.clone(),
             keys: Vec::new(),
             bounds: (0, 0),
-            paths: HashMap::new(),
+            paths: HashMultiMap::new(),
             p_to_k: HashMap::new(),
             k_to_p: HashMap::new(),
             point_set: HashSet::new(),
@@ -103,7 +106,11 @@ impl Keypad {
         }
     }
 
-    pub fn parse(&mut self) {
+    pub fn parse(&mut self) -> AocResult<()> {
+        let Some(first) = self.raw.lines().next() else {
+            return Err(AocHelperError::parse_error(1, 1, "empty keypad"));
+        };
+        let mc = first.chars().count();
         for (row, line) in self.raw.lines().into_iter().enumerate() {
             for (col, char) in line.chars().enumerate() {
                 let p: Point = (row as i32, col as i32);
@@ -117,8 +124,8 @@ impl Keypad {
             }
         }
         let mr = self.raw.lines().count();
-        let mc = self.raw.lines().next().unwrap().chars().count();
         self.bounds = (mr as i32, mc as i32);
+        Ok(())
     }
 
     fn eval_path(
@@ -185,7 +192,7 @@ impl Keypad {
                 }
 
                 if pp.len() > 0 {
-                    append_to_hash_set(&mut self.paths, (*start_char, res.0), pp);
+                    self.paths.insert((*start_char, res.0), pp);
                 }
             }
         }
@@ -248,29 +255,35 @@ struct Day21 {
 }
 
 impl Day21 {
-    pub fn new(s: &String) -> Self {
+    pub fn new(s: &String) -> AocResult<Self> {
         let mut np = Keypad::new(&NUMBER_PAD.to_string());
-        np.parse();
+        np.parse()?;
         np.find_all(6);
-        debug!("{:?}", np.paths.keys());
+        debug!("{:?}", np.paths.keys().collect::<Vec<_>>());
         np.make_string_paths();
 
         let mut dp = Keypad::new(&DIR_PAD.to_string());
-        dp.parse();
+        dp.parse()?;
         dp.find_all(4);
         dp.make_string_paths();
-        Self {
+        Ok(Self {
             raw: s.clone(),
             targets: Vec::new(),
             numpad: np,
             dirpad: dp,
-        }
+        })
     }
 
-    pub fn parse(&mut self) {
-        self.raw
-            .lines()
-            .for_each(|l| self.targets.push(l.to_string()));
+    pub fn parse(&mut self) -> AocResult<()> {
+        for (idx, l) in self.raw.lines().enumerate() {
+            // get_answer multiplies by the numeric part, make sure there is one
+            let Some((num, _)) = l.split_once('A') else {
+                return Err(AocHelperError::parse_error(idx + 1, 1, "expected a code ending in A"));
+            };
+            parse_token::<usize>(idx, l, num)?;
+            self.targets.push(l.to_string());
+        }
+        Ok(())
     }
 
     // return the shortest possible sequence for this path?
@@ -361,27 +374,34 @@ Find the shortest paths on the number pad, represented as a vector of directions
 
 */
 
+#[derive(Default)]
+pub struct Day21Solution {
+    d21: Option<Day21>,
+}
+
+impl Solution for Day21Solution {
+    fn day(&self) -> u32 {
+        21
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d21 = Day21::new(&input.to_string())?;
+        d21.parse()?;
+        self.d21 = Some(d21);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d21 = self.d21.as_ref()?;
+        Some(d21.get_answer(3).to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d21 = self.d21.as_ref()?;
+        Some(d21.get_answer(26).to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(21, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d21 = Day21::new(&test_input);
-    d21.parse();
-    let ans = d21.get_answer(3);
-    info!("Ans: {ans}");
-
-    let mut d21 = Day21::new(&real_input);
-    d21.parse();
-    let ans = d21.get_answer(3);
-    info!("Ans: {ans}");
-
-    let mut d21 = Day21::new(&real_input);
-    d21.parse();
-    let ans = d21.get_answer(26);
-    info!("Ans: {ans}");
+    run_standalone::<Day21Solution>();
 }
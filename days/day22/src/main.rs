@@ -1,8 +1,8 @@
 use std::collections::HashMap;
 
-use aoc2024::{AocHelper, RequestedAocInputType};
+use aoc2024::{parse_token, runner::run_standalone, solution::Solution, AocResult};
 use itertools::Itertools;
-use tracing::{debug, info};
+use tracing::debug;
 
 const PRUNE_CONST: i64 = 16777216 - 1;
 type Sequence = [i64; 4];
@@ -14,13 +14,17 @@ struct Day22 {
 }
 
 impl Day22 {
-    pub fn new(s: &String) -> Self {
-        let nums: Vec<i64> = s.lines().map(|l| l.parse::<i64>().unwrap()).collect();
-
-        Self {
+    pub fn new(s: &String) -> AocResult<Self> {
+        let nums: Vec<i64> = s
+            .lines()
+            .enumerate()
+            .map(|(idx, l)| parse_token(idx, l, l))
+            .collect::<AocResult<_>>()?;
+
+        Ok(Self {
             raw: s.clone(),
             init_nums: nums,
-        }
+        })
     }
 
     pub fn single_iter(start: i64) -> (i64, i64, i64) {
@@ -100,32 +104,32 @@ impl Day22 {
     }
 }
 
+#[derive(Default)]
+pub struct Day22Solution {
+    d22: Option<Day22>,
+}
+
+impl Solution for Day22Solution {
+    fn day(&self) -> u32 {
+        22
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        self.d22 = Some(Day22::new(&input.to_string())?);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d22 = self.d22.as_ref()?;
+        Some(d22.get_2kth_numbers().to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d22 = self.d22.as_ref()?;
+        Some(d22.get_best_score().to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(22, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let d22 = Day22::new(&test_input);
-    debug!("{:?}", d22);
-    let ans = d22.get_2kth_numbers();
-    info!("Ans: {ans}");
-
-    let d22 = Day22::new(&real_input);
-    debug!("{:?}", d22);
-    let ans = d22.get_2kth_numbers();
-    info!("Ans: {ans}");
-
-    let d22 = Day22::new(&test_input);
-    debug!("{:?}", d22);
-    let ans = d22.get_best_score();
-    info!("Ans: {}", ans);
-
-    let d22 = Day22::new(&real_input);
-    debug!("{:?}", d22);
-    let ans = d22.get_best_score();
-    info!("Ans: {}", ans);
+    run_standalone::<Day22Solution>();
 }
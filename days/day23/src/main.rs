@@ -1,18 +1,36 @@
-use std::collections::{HashMap, HashSet};
-
 use aoc2024::{
-    counter::HashMapCounter,
-    map_vec_extend::append_to_hash_set,
-    AocHelper, RequestedAocInputType,
+    graph::Graph,
+    runner::run_standalone,
+    solution::Solution,
+    AocHelperError, AocResult,
 };
 use itertools::Itertools;
 use tracing::{debug, info};
 
+/// Computer names are always two letters; an array keeps them `Copy` for `Graph`.
+type Computer = [char; 2];
+
+fn name(c: &Computer) -> String {
+    c.iter().collect()
+}
+
+fn parse_computer(idx: usize, col: usize, s: &str) -> AocResult<Computer> {
+    let chars: Vec<char> = s.chars().collect();
+    match chars[..] {
+        [a, b] => Ok([a, b]),
+        _ => Err(AocHelperError::parse_error(
+            idx + 1,
+            col,
+            format!("`{s}` is not a two-letter computer name"),
+        )),
+    }
+}
+
 #[derive(Debug)]
 struct Day23 {
     raw: String,
-    pairs: Vec<(String, String)>,
-    maps: HashMap<String, HashSet<String>>,
+    pairs: Vec<(Computer, Computer)>,
+    graph: Graph<Computer>,
 }
 
 impl Day23 {
@@ -20,123 +38,76 @@ impl Day23 {
         Self {
             raw: s.clone(),
             pairs: Vec::new(),
-            maps: HashMap::new(),
+            graph: Graph::new(),
         }
     }
 
-    pub fn parse(&mut self) {
-        for ele in self.raw.lines() {
-            let parts = ele.split_once('-').unwrap();
-            self.pairs.push((parts.0.to_string(), parts.1.to_string()));
+    pub fn parse(&mut self) -> AocResult<()> {
+        for (idx, ele) in self.raw.lines().enumerate() {
+            let Some(parts) = ele.split_once('-') else {
+                return Err(AocHelperError::parse_error(idx + 1, 1, "expected `a-b`"));
+            };
+            let a = parse_computer(idx, 1, parts.0)?;
+            let b = parse_computer(idx, parts.0.len() + 2, parts.1)?;
+            self.pairs.push((a, b));
         }
+        Ok(())
     }
 
     pub fn make_sets(&mut self) {
-        for ele in self.pairs.iter() {
-            // append_to_hash_set(&mut self.maps, ele.0.clone(), ele.0.clone());
-            append_to_hash_set(&mut self.maps, ele.0.clone(), ele.1.clone());
-            append_to_hash_set(&mut self.maps, ele.1.clone(), ele.0.clone());
-            // append_to_hash_set(&mut self.maps, ele.1.clone(), ele.1.clone());
+        for (a, b) in self.pairs.iter() {
+            self.graph.add_undirected_edge(a, b, 1);
         }
     }
 
+    /// Triangles of computers with at least one name starting with `t`.
     pub fn counter_sets(&self) -> usize {
-        let mut c: HashMapCounter<String> = HashMapCounter::new();
-        for (key, set) in self.maps.iter() {
-            let starts_valid = key.starts_with('t');
-            let mut valid_set: HashSet<String> = HashSet::new();
-            for window in set.iter().combinations(2) {
-                // debug!("Window {:?}", window);
-                let mut valid: bool = starts_valid;
-                let mut point_list_vec: Vec<String> = Vec::new();
-                point_list_vec.push(key.clone());
-                for point in window.iter() {
-                    point_list_vec.push(point.to_string());
-                    if point.starts_with('t') {
-                        valid = true;
-                    }
-                }
-                if valid {
-                    point_list_vec.sort();
-                    let pls = point_list_vec.join(",");
-                    valid_set.insert(pls);
-                }
-            }
-            for pls in valid_set.iter() {
-                c.add(pls.to_string());
-            }
-        }
-        let mut sats: Vec<String> = Vec::new();
-        for (group, len) in c.iter() {
-            debug!("Group {group} len {len}");
-            if len >= &3 {
-                debug!("Group {group} satisfies!");
-                sats.push(group.to_string());
-            }
+        let triangles = self.graph.k_cliques(3);
+        for t in triangles.iter() {
+            debug!("Triangle {}", t.iter().map(name).join(","));
         }
-        for s in sats.iter() {
-            info!("{}", s);
-        }
-        sats.len()
+        triangles
+            .iter()
+            .filter(|t| t.iter().any(|c| c[0] == 't'))
+            .count()
     }
 
     pub fn largest_continent(&self) -> String {
-        let mut c: HashMapCounter<String> = HashMapCounter::new();
-        for (key, set) in self.maps.iter() {
-            let mut valid_set: HashSet<String> = HashSet::new();
-            for window in set.iter().powerset() {
-                // debug!("Window {:?}", window);
-                let mut point_list_vec: Vec<String> = Vec::new();
-                point_list_vec.push(key.clone());
-                for point in window.iter() {
-                    point_list_vec.push(point.to_string());
-                    if point.starts_with('t') {}
-                }
-                point_list_vec.sort();
-                let pls = point_list_vec.join(",");
-                valid_set.insert(pls);
-            }
-            for pls in valid_set.iter() {
-                c.add(pls.to_string());
-            }
-        }
-        let mut sorted = c.iter().sorted_by(|a, b| b.1.cmp(&a.1));
-        sorted.next().unwrap().0.to_string()
+        let party = self.graph.maximum_clique();
+        info!("Largest party has {} computers", party.len());
+        party.iter().map(name).join(",")
     }
 }
 
-fn main() {
-    let aoc: AocHelper = AocHelper::new(23, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
+#[derive(Default)]
+pub struct Day23Solution {
+    d23: Option<Day23>,
+}
 
-    let mut d23 = Day23::new(&test_input);
-    d23.parse();
-    d23.make_sets();
-    debug!("{:?}", d23);
-    let ans = d23.counter_sets();
-    info!("Answer: {ans}");
+impl Solution for Day23Solution {
+    fn day(&self) -> u32 {
+        23
+    }
 
-    let mut d23 = Day23::new(&real_input);
-    d23.parse();
-    d23.make_sets();
-    debug!("{:?}", d23);
-    let ans = d23.counter_sets();
-    info!("Answer: {ans}");
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d23 = Day23::new(&input.to_string());
+        d23.parse()?;
+        d23.make_sets();
+        self.d23 = Some(d23);
+        Ok(())
+    }
 
-    let mut d23 = Day23::new(&test_input);
-    d23.parse();
-    d23.make_sets();
-    let ans = d23.largest_continent();
-    info!("Answer: {ans}");
+    fn part1(&mut self) -> Option<String> {
+        let d23 = self.d23.as_ref()?;
+        Some(d23.counter_sets().to_string())
+    }
 
-    let mut d23 = Day23::new(&real_input);
-    d23.parse();
-    d23.make_sets();
-    let ans = d23.largest_continent();
-    info!("Answer: {ans}");
+    fn part2(&mut self) -> Option<String> {
+        let d23 = self.d23.as_ref()?;
+        Some(d23.largest_continent())
+    }
+}
+
+fn main() {
+    run_standalone::<Day23Solution>();
 }
@@ -1,15 +1,16 @@
 use std::error::Error;
 use std::hash::Hash;
-use std::io::prelude::*;
+use std::time::Instant;
 use std::{
     borrow::BorrowMut,
     cell::RefCell,
     collections::{HashMap, HashSet},
-    fs::File,
 };
 
-use aoc2024::map_vec_extend::append_to_hash_map;
-use aoc2024::{AocHelper, AocHelperError, AocResult, RequestedAocInputType};
+use aoc2024::multimap::HashMultiMap;
+use aoc2024::{
+    parse_token, runner::run_standalone, solution::Solution, AocHelperError, AocResult,
+};
 use itertools::Itertools;
 use regex::Regex;
 use tracing::{debug, error, info};
@@ -49,12 +50,20 @@ impl Day24 {
         }
     }
 
-    pub fn parse(&mut self) {
-        let (init_state, gates) = self.raw.split_once("\n\n").unwrap();
+    pub fn parse(&mut self) -> AocResult<()> {
+        let Some((init_state, gates)) = self.raw.split_once("\n\n") else {
+            return Err(AocHelperError::parse_error(
+                1,
+                1,
+                "expected a blank line between the wires and the gates",
+            ));
+        };
         debug!("IS: {:?} |||| G: {:?}", init_state, gates);
-        for line in init_state.lines() {
-            let (var_name, value) = line.split_once(": ").unwrap();
-            let bool_val: bool = value.parse::<i32>().unwrap() == 1;
+        for (idx, line) in init_state.lines().enumerate() {
+            let Some((var_name, value)) = line.split_once(": ") else {
+                return Err(AocHelperError::parse_error(idx + 1, 1, "expected `wire: value`"));
+            };
+            let bool_val: bool = parse_token::<i32>(idx, line, value)? == 1;
             self.start_state.insert(var_name.to_string(), bool_val);
             let static_output = SingleOutput {
                 name: var_name.to_string(),
@@ -106,6 +115,7 @@ impl Day24 {
             self.gates.insert(out.to_string(), RefCell::new(so));
             self.missing_count += 1;
         }
+        Ok(())
     }
 
     pub fn single_step(&mut self) {
@@ -205,6 +215,7 @@ impl Day24 {
     }
 
     pub fn solve_p1(&mut self) -> AocResult<u128 >{
+        let start = Instant::now();
         let mut last = 0;
         let mut consec = 0;
         while self.missing_count > 0 {
@@ -215,7 +226,9 @@ impl Day24 {
             last = self.missing_count;
 
             if consec >= 20 {
-                return Err(AocHelperError::TimeoutError);
+                return Err(AocHelperError::TimeoutError {
+                    elapsed: start.elapsed(),
+                });
             }
 
         }
@@ -617,11 +630,11 @@ subgraph {{
         (cout_name_logical, cout_logical_name)
     }
 
-    pub fn combine_maps(&self, v: Vec<&HashMap<String,String>>) -> (Vec<(String, Vec<String>)>, HashMap<String, Vec<String>>) {
-        let mut comb: HashMap<String, Vec<String>> = HashMap::new();
+    pub fn combine_maps(&self, v: Vec<&HashMap<String,String>>) -> (Vec<(String, Vec<String>)>, HashMultiMap<String, Vec<String>>) {
+        let mut comb: HashMultiMap<String, Vec<String>> = HashMultiMap::new();
         for hm in v.iter() {
             hm.iter().for_each(|(k, v)| {
-                append_to_hash_map(&mut comb, k.clone(), v.clone());
+                comb.insert(k.clone(), v.clone());
             });
         }
         let mut out: Vec<(String, Vec<String>)> = Vec::new();
@@ -633,7 +646,7 @@ subgraph {{
         (out, comb)
     }
 
-    pub fn find_unsafes(&self, comb_hm: &HashMap<String, Vec<String>>) -> HashSet<String> {
+    pub fn find_unsafes(&self, comb_hm: &HashMultiMap<String, Vec<String>>) -> HashSet<String> {
         let mut pot_swaps: HashSet<String> = HashSet::new();
         for (key, all_names) in comb_hm.iter() {
             let mut ani = all_names.iter();
@@ -658,9 +671,9 @@ subgraph {{
         pot_swaps
     }
 
-    pub fn re_render(&self, gate_name: &str, combined_map: &HashMap<String,Vec<String>>) {
+    pub fn re_render(&self, gate_name: &str, combined_map: &HashMultiMap<String, Vec<String>>) {
         let b = vec!["NO_COOL_NAME".to_string()];
-        let nice_name = combined_map.get(gate_name).unwrap_or(&b);
+        let nice_name = combined_map.get(&gate_name.to_string()).unwrap_or(&b);
         let g = &self.gates.get(gate_name).unwrap().borrow();
         match &g.predecessors {
             WireCombo::Or { a, b } => {
@@ -694,9 +707,9 @@ subgraph {{
 
 }
 
-fn run_a_check(real_input: &String, swaps: Vec<Vec<&String>>) -> usize {
+fn run_a_check(real_input: &String, swaps: Vec<Vec<&String>>) -> AocResult<usize> {
     let mut d24 = Day24::new(real_input);
-    d24.parse();
+    d24.parse()?;
     for s in swaps.iter() {
         d24.swap(s[0].as_str(), s[1].as_str());
     }
@@ -752,119 +765,33 @@ fn run_a_check(real_input: &String, swaps: Vec<Vec<&String>>) -> usize {
         d24.re_render(s.as_str(), &combined0.1);
     }
 
-    total_swaps.len()
+    Ok(total_swaps.len())
 }
 
-fn main() {
-    let aoc: AocHelper = AocHelper::new(24, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d24 = Day24::new(&test_input);
-    d24.parse();
-    debug!("{:?}", d24);
-    d24.single_step();
-    let res = d24.solve_p1().unwrap();
-    info!("Answer: {res}");
-
-    let mut d24 = Day24::new(&real_input);
-    d24.parse();
-    debug!("{:?}", d24);
-    d24.single_step();
-    let res = d24.solve_p1().unwrap();
-    info!("Answer: {res}");
-
-    // d24.get_inputs_per_output_bit();
-
-    let gv = d24.make_graphviz_graph();
-    let mut file = File::create("foo.txt").unwrap();
-    file.write_all(gv.as_bytes());
-
-    let mut d24 = Day24::new(&real_input);
-    d24.parse();
-    // d24.swap("jdr", "z31");
-    // d24.swap("pgt", "z22");
-    // d24.swap("ffj", "z08");
-    // d24.swap("gjh", "z22");
-    // d24.swap("jdr", "z31");
-    d24.swap("ffj", "z08");
-    d24.swap("gjh", "z22");
-    d24.swap("jdr", "z31");
-    d24.swap("dwp", "kfm");
-    d24.make_graphviz_graph();
-    d24.check_layers();
-    d24.correct_output();
-    let name_map = d24.find_input_xor_and_gates();
-    info!("{:#?}", name_map);
-
-    let cin_maps = d24.calculate_cins(&name_map.0);
-    info!("{:#?}", cin_maps);
-
-    let interadd_maps = d24.find_intermediate_ands(&cin_maps.0, &name_map.0);
-    info!("{:#?}", interadd_maps);
-
-    let cout_maps = d24.find_couts(&interadd_maps.0, &name_map.0);
-    info!("{:#?}", cout_maps);
-
-    let combined0 = d24.combine_maps(vec![&name_map.0, &cin_maps.0, &interadd_maps.0, &cout_maps.0]);
-    let combined1= d24.combine_maps(vec![&name_map.1, &cin_maps.1, &interadd_maps.1, &cout_maps.1]);
+#[derive(Default)]
+pub struct Day24Solution {
+    d24: Option<Day24>,
+}
 
-    info!("{:?}", combined0);
-    for cmb in combined0.0 {
-        info!("{}: {:?}", cmb.0, cmb.1);
+impl Solution for Day24Solution {
+    fn day(&self) -> u32 {
+        24
     }
-    for cmb in combined1.0 {
-        info!("{}: {:?}", cmb.0, cmb.1);
-    }
-
-    let p_swaps = d24.find_unsafes(&combined0.1);
-    let of_concern = cin_maps.2;
-    info!("P Swaps: {:?}", p_swaps);
-    info!("Z Swaps: {:?}", of_concern);
 
-    let mut total_swaps: Vec<String> = Vec::new();
-    for p in p_swaps.iter() {
-        if p != "rvh" && p != "z45" {
-            total_swaps.push(p.clone())
-        }
-    }
-    for p in of_concern.iter() {
-        if p != "rvh" && p != "z45" {
-            total_swaps.push(p.clone())
-        }
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d24 = Day24::new(&input.to_string());
+        d24.parse()?;
+        self.d24 = Some(d24);
+        Ok(())
     }
 
-    info!("{} total swap targets: {:?}", total_swaps.len(), total_swaps);
-
-    for s in total_swaps.iter() {
-        d24.re_render(s.as_str(), &combined0.1);
+    fn part1(&mut self) -> Option<String> {
+        let d24 = self.d24.as_mut()?;
+        let res = d24.solve_p1().ok()?;
+        Some(res.to_string())
     }
-    // d24.calc_value(start);
-    let out = d24.correct_output();
-    info!("Out: {out}");
-
-    // let mut all_eights = total_swaps.iter().combinations(8);
-    // // let all_keys = d24.gates.keys();
-    // // let mut all_eights = all_keys.combinations(8);
-
-    // for window in all_eights {
-    //     let mut chunks = window.chunks(2);
-    //     let chunks: Vec<Vec<&String>> = chunks.map(|x| {
-    //         Vec::from(x)
-    //     }).collect();
-    //     let wrong = run_a_check(&real_input, chunks.clone());
-    //     if wrong != 12345{
-    //         error!("Window {:?} has {wrong} wrong", window);
-    //     }
-    //     if wrong == 0 {
-    //         return;
-    //     }
-    // }
-
-
+}
 
+fn main() {
+    run_standalone::<Day24Solution>();
 }
@@ -1,5 +1,8 @@
 use std::{cell::RefCell, collections::{HashMap, HashSet}, rc::Rc};
-use aoc2024::{map_vec_extend::append_to_hash_map, AocHelper, AocHelperError, AocResult, RequestedAocInputType};
+use aoc2024::{
+    multimap::HashMultiMap, runner::run_standalone, solution::Solution,
+    AocHelperError, AocResult, RequestedAocInputType,
+};
 use itertools::all;
 use regex::Regex;
 use tracing::{debug, error, info};
@@ -26,7 +29,7 @@ struct Day24Part2 {
     all_gates: HashMap<String, Wire>,
     inputs: Vec<(String, Wire)>,
     outputs: Vec<(String, Wire)>,
-    gates_from_input: HashMap<String, Vec<Wire>>,
+    gates_from_input: HashMultiMap<String, Vec<Wire>>,
     swaps: Vec<(String, String)>,
     touched_gate_names: HashSet<String>,
 }
@@ -38,7 +41,7 @@ impl Day24Part2 {
             all_gates: HashMap::new(),
             inputs: Vec::new(),
             outputs: Vec::new(),
-            gates_from_input: HashMap::new(),
+            gates_from_input: HashMultiMap::new(),
             swaps: Vec::new(),
             touched_gate_names: HashSet::new(),
         }
@@ -66,10 +69,10 @@ impl Day24Part2 {
 
 
     // will trim input to ignore reg start states for part b
-    pub fn parse(&mut self) {
+    pub fn parse(&mut self) -> AocResult<()> {
         let gates = self.raw.clone();
         let gate_regex = Regex::new(COMBINATION_REGEX_STR).unwrap();
-        for line in gates.lines() {
+        for (idx, line) in gates.lines().enumerate() {
             let Some(caps) = gate_regex.captures(line) else {
                 continue;
             };
@@ -87,7 +90,7 @@ impl Day24Part2 {
                     GateType::Xor
                 }
                 _ => {
-                    panic!("Bad line: {line}");
+                    return Err(AocHelperError::parse_error_at(idx, line, op, "unknown gate type"));
                 }
             };
             let wire = Wire{
@@ -95,8 +98,8 @@ impl Day24Part2 {
                 gate_type: gt,
                 input_names: vec![a.to_string(), b.to_string()]
             };
-            append_to_hash_map(&mut self.gates_from_input, a.to_string(), wire.clone());
-            append_to_hash_map(&mut self.gates_from_input, b.to_string(), wire.clone());
+            self.gates_from_input.insert(a.to_string(), wire.clone());
+            self.gates_from_input.insert(b.to_string(), wire.clone());
 
             self.all_gates.insert(out.to_string(), wire.clone());
             if a.starts_with("x") || b.starts_with("x") || a.starts_with("y") || b.starts_with("y") {
@@ -110,6 +113,7 @@ impl Day24Part2 {
             });
         }
         
+        Ok(())
     }
 
     fn get_input_gates(&self, w: &Wire) -> Vec<&Wire> {
@@ -333,44 +337,53 @@ impl Day24Part2 {
 
 }
 
-fn main() {
-    let aoc: AocHelper = AocHelper::new(24, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d24b = Day24Part2::new(&real_input);
-    d24b.swap("ffj", "z08");
-    d24b.swap("gjh", "z22");
-    d24b.swap("jdr", "z31");
-    d24b.swap("dwp", "kfm");
-    d24b.parse();
-    debug!("{:#?}", d24b.outputs);
-    let uxor = d24b.unusual_xors();
-    info!("Out of place xors: {:?}", uxor);
-    d24b.make_an_adder(1);
-    // return;
-
-    let mut next_cin = "rvh".to_string();
-    for i in 1..44 {
-        let res= d24b.compare_gates_to_ideal(i, &next_cin);
-        match res {
-            Ok(ans) => {
-                match ans {
-                    Some(out) => {
-                        next_cin = out;
-                    }
-                    None => {}
-                }
+#[derive(Default)]
+pub struct Day24Solution {
+    d24b: Option<Day24Part2>,
+    is_example: bool,
+}
+
+impl Solution for Day24Solution {
+    fn day(&self) -> u32 {
+        24
+    }
+
+    fn configure(&mut self, input_type: &RequestedAocInputType) {
+        self.is_example = input_type.is_example();
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d24b = Day24Part2::new(&input.to_string());
+        // the swaps below were found by hand for the real input
+        d24b.swap("ffj", "z08");
+        d24b.swap("gjh", "z22");
+        d24b.swap("jdr", "z31");
+        d24b.swap("dwp", "kfm");
+        d24b.parse()?;
+        self.d24b = Some(d24b);
+        Ok(())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        if self.is_example {
+            return None;
+        }
+        let d24b = self.d24b.as_mut()?;
+        let uxor = d24b.unusual_xors();
+        info!("Out of place xors: {:?}", uxor);
+        d24b.make_an_adder(1);
+
+        let mut next_cin = "rvh".to_string();
+        for i in 1..44 {
+            if let Ok(Some(out)) = d24b.compare_gates_to_ideal(i, &next_cin) {
+                next_cin = out;
             }
-            Err(_) => {}
         }
+
+        Some(d24b.render_swap_list())
     }
+}
 
-    let ans = d24b.render_swap_list();
-    info!("Finally!! {ans}");
-    
+fn main() {
+    run_standalone::<Day24Solution>();
 }
@@ -1,9 +1,9 @@
 use std::{collections::HashSet};
 
-use aoc2024::{AocHelper, RequestedAocInputType};
+use aoc2024::{runner::run_standalone, solution::Solution, AocHelperError, AocResult};
 use rayon::iter::IntoParallelRefIterator;
 use rayon::prelude::*;
-use tracing::{debug, info};
+use tracing::debug;
 
 #[derive(Debug)]
 struct Day25 {
@@ -23,12 +23,14 @@ impl Day25 {
         }
     }
 
-    pub fn parse(&mut self) {
+    pub fn parse(&mut self) -> AocResult<()> {
         let grids = self.raw.split("\n\n");
         for grid in grids {
             let mut code: [usize;5] = [0,0,0,0,0];
             let mut lines = grid.lines();
-            let zeroth = lines.next().unwrap();
+            let Some(zeroth) = lines.next() else {
+                return Err(AocHelperError::parse_error_in(&self.raw, grid, "empty schematic"));
+            };
             let is_lock: bool;
 
             if zeroth.contains("#") {
@@ -44,6 +46,13 @@ impl Day25 {
                     if val == '.' {
                         continue;
                     }
+                    if col >= code.len() {
+                        return Err(AocHelperError::parse_error_in(
+                            &self.raw,
+                            &line[col..],
+                            "schematics are 5 columns wide",
+                        ));
+                    }
 
                     if code[col] >= code_height {
                         continue;
@@ -64,6 +73,7 @@ impl Day25 {
 {}
         has code: {:?}", grid, code);
         }
+        Ok(())
     }
 
     pub fn find_match_count(&self) -> usize {
@@ -88,24 +98,29 @@ impl Day25 {
     }
 }
 
-fn main() {
-    let aoc: AocHelper = AocHelper::new(25, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
+#[derive(Default)]
+pub struct Day25Solution {
+    d25: Option<Day25>,
+}
+
+impl Solution for Day25Solution {
+    fn day(&self) -> u32 {
+        25
+    }
 
-    let mut d25 = Day25::new(&test_input);
-    d25.parse();
-    debug!("{:?}", d25);
-    let ans = d25.find_match_count();
-    info!("Ans: {}", ans);
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d25 = Day25::new(&input.to_string());
+        d25.parse()?;
+        self.d25 = Some(d25);
+        Ok(())
+    }
 
-    let mut d25 = Day25::new(&real_input);
-    d25.parse();
-    debug!("{:?}", d25);
-    let ans = d25.find_match_count();
-    info!("Ans: {}", ans);
+    fn part1(&mut self) -> Option<String> {
+        let d25 = self.d25.as_ref()?;
+        Some(d25.find_match_count().to_string())
+    }
+}
+
+fn main() {
+    run_standalone::<Day25Solution>();
 }
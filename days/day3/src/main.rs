@@ -1,4 +1,4 @@
-use aoc2024::{AocHelper, RequestedAocInputType};
+use aoc2024::{parse_token_in, runner::run_standalone, solution::Solution, AocResult};
 use regex::Regex;
 use tracing::{debug, info};
 struct Day3 {
@@ -17,22 +17,23 @@ impl Day3 {
         }
     }
 
-    pub fn get_muls(&mut self) {
+    pub fn get_muls(&mut self) -> AocResult<()> {
         let re = Regex::new(STANDARD_MUL_REGEX).unwrap();
         let muls: Vec<&str> = re.find_iter(&self.raw).map(|m| m.as_str()).collect();
         debug!("Found muls {:?}", muls);
 
-        re.captures_iter(&self.raw).for_each(|caps| {
+        for caps in re.captures_iter(&self.raw) {
             debug!("Caps: {:?}", caps);
-            let a = caps.get(1).unwrap().as_str().parse::<i32>().unwrap();
-            let b = caps.get(2).unwrap().as_str().parse::<i32>().unwrap();
+            let a: i32 = parse_token_in(&self.raw, &caps[1])?;
+            let b: i32 = parse_token_in(&self.raw, &caps[2])?;
             let c = [a, b];
             info!("Found nums {:?}", c);
             self.muls.push([a, b]);
-        });
+        }
+        Ok(())
     }
 
-    pub fn get_optional_muls(&mut self) {
+    pub fn get_optional_muls(&mut self) -> AocResult<()> {
         let re: Regex = Regex::new(DO_DONT_REGEX).unwrap();
 
         let mut allowed: bool = true;
@@ -55,8 +56,8 @@ impl Day3 {
                     if !allowed {
                         continue;
                     }
-                    let a = cc[2].parse::<i32>().unwrap();
-                    let b = cc[3].parse::<i32>().unwrap();
+                    let a: i32 = parse_token_in(&self.raw, cc[2])?;
+                    let b: i32 = parse_token_in(&self.raw, cc[3])?;
                     let c = [a, b];
                     info!("Found nums {:?}", c);
                     self.muls.push(c);
@@ -70,6 +71,7 @@ impl Day3 {
                 [_, _, _] => {}
             }
         }
+        Ok(())
     }
 
     pub fn get_mul_sum(&self) -> i32 {
@@ -77,38 +79,39 @@ impl Day3 {
     }
 }
 
-fn main() {
-    let aoc: AocHelper = AocHelper::new(3, Some(vec!["dont_test".to_string()]));
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
+#[derive(Default)]
+pub struct Day3Solution {
+    d3: Option<Day3>,
+    d3_optional: Option<Day3>,
+}
 
-    let second_test_input = aoc
-        .get_input_as_string(RequestedAocInputType::CustomTest {
-            fname: "dont_test".to_string(),
-        })
-        .unwrap();
+impl Solution for Day3Solution {
+    fn day(&self) -> u32 {
+        3
+    }
 
-    let mut d3 = Day3::new(&test_input);
-    d3.get_muls();
-    let total = d3.get_mul_sum();
-    info!("Got answer: {total}");
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let raw = input.to_string();
+        let mut d3 = Day3::new(&raw);
+        d3.get_muls()?;
+        let mut d3_optional = Day3::new(&raw);
+        d3_optional.get_optional_muls()?;
+        self.d3 = Some(d3);
+        self.d3_optional = Some(d3_optional);
+        Ok(())
+    }
 
-    let mut d3 = Day3::new(&real_input);
-    d3.get_muls();
-    let total = d3.get_mul_sum();
-    info!("Got answer: {total}");
+    fn part1(&mut self) -> Option<String> {
+        let d3 = self.d3.as_ref()?;
+        Some(d3.get_mul_sum().to_string())
+    }
 
-    let mut d3 = Day3::new(&second_test_input);
-    d3.get_optional_muls();
-    let total = d3.get_mul_sum();
-    info!("Got answer: {total}");
+    fn part2(&mut self) -> Option<String> {
+        let d3 = self.d3_optional.as_ref()?;
+        Some(d3.get_mul_sum().to_string())
+    }
+}
 
-    let mut d3 = Day3::new(&real_input);
-    d3.get_optional_muls();
-    let total = d3.get_mul_sum();
-    info!("Got answer: {total}");
+fn main() {
+    run_standalone::<Day3Solution>();
 }
@@ -1,7 +1,6 @@
-use aoc2024::{AocHelper, RequestedAocInputType};
-use diagonal::{diagonal_pos_neg, diagonal_pos_pos};
+use aoc2024::{grid::Grid, runner::run_standalone, solution::Solution, AocHelperError, AocResult};
 use regex::Regex;
-use tracing::{debug, error, info};
+use tracing::{debug, info};
 
 struct Day4 {
     raw: String,
@@ -16,74 +15,25 @@ impl Day4 {
         }
     }
 
-    fn set_char(v: &mut Vec<Vec<char>>, x: usize, y: usize, c: char) {
-        if let Some(inner_vec) = v.get_mut(x) {
-            if let Some(char_ref) = inner_vec.get_mut(y) {
-                *char_ref = c;
-            } else {
-                error!("Failed to set char at y: {}", y);
-            }
-        } else {
-            error!("Failed to set char at x: {}", x);
-        }
-    }
-
-    pub fn make_all_strings(&mut self) {
-        let row_count = self.raw.lines().count();
-        let row_len = self.raw.lines().next().unwrap().len();
-
-        info!("Row count: {} len: {}", row_count, row_len);
-
-        let dimension = row_count.max(row_len);
-        debug!("Dim: {dimension}");
-
-        let empty_vec: Vec<char> = vec!['*'; dimension];
-
-        let mut forward: Vec<String> = Vec::new();
-        let mut updown: Vec<Vec<char>> = vec![empty_vec.clone(); dimension];
-
-        debug!("Empty vec: {:?}", empty_vec);
-
-        for (line_no, line) in self.raw.lines().enumerate() {
-            forward.push(line.to_string());
-            for (cno, cur_char) in line.to_string().chars().enumerate() {
-                Day4::set_char(&mut updown, cno, line_no, cur_char);
-            }
+    pub fn make_all_strings(&mut self) -> AocResult<()> {
+        let grid = Grid::parse(&self.raw, |c| c)?;
+        if grid.height() == 0 {
+            return Err(AocHelperError::parse_error(1, 1, "empty input"));
         }
+        info!("Row count: {} len: {}", grid.height(), grid.width());
 
-        let botleft = diagonal_pos_neg(&updown);
-        let botright = diagonal_pos_pos(&updown);
+        let forward: Vec<String> = grid.rows().map(|row| row.collect()).collect();
+        let updown: Vec<String> = grid.cols().map(|col| col.collect()).collect();
+        let botright: Vec<String> = grid.diagonals().map(|d| d.collect()).collect();
+        let botleft: Vec<String> = grid.anti_diagonals().map(|d| d.collect()).collect();
         debug!("bl: {:?}", botleft);
         debug!("br: {:?}", botright);
 
-        let botleft: Vec<String> = botleft
-            .iter()
-            .map(|x| {
-                let charss: String = x.iter().map(|c| **c).collect();
-                charss
-            })
-            .collect();
-
-        let botright: Vec<String> = botright
-            .iter()
-            .map(|x| {
-                let charss: String = x.iter().map(|c| **c).collect();
-                charss
-            })
-            .collect();
-
-        let updown: Vec<String> = updown
-            .iter()
-            .map(|x| {
-                let charss: String = x.iter().map(|c| *c).collect();
-                charss
-            })
-            .collect();
-
-        self.all_strings.push(forward.clone());
-        self.all_strings.push(updown.clone());
-        self.all_strings.push(botleft.clone());
-        self.all_strings.push(botright.clone());
+        self.all_strings.push(forward);
+        self.all_strings.push(updown);
+        self.all_strings.push(botleft);
+        self.all_strings.push(botright);
+        Ok(())
     }
 
     pub fn count_xmas(&self) -> usize {
@@ -103,117 +53,62 @@ impl Day4 {
 }
 
 struct Day4PartB {
-    raw: String,
-    char_vec: Vec<Vec<char>>,
+    grid: Grid<char>,
 }
 
 impl Day4PartB {
-    pub fn new(s: &String) -> Self {
-        Self {
-            raw: s.clone(),
-            char_vec: Vec::new(),
-        }
+    pub fn new(s: &str) -> AocResult<Self> {
+        Ok(Self {
+            grid: Grid::parse(s, |c| c)?,
+        })
     }
 
-    pub fn to_vv(&mut self) {
-        self.char_vec = self
-            .raw
-            .lines()
-            .into_iter()
-            .map(|line| line.chars().collect::<Vec<char>>())
-            .collect();
+    /// Counts the `A`s with `MAS` running through them along both diagonals.
+    pub fn find_cross_mas(&self) -> usize {
+        let is_mas = |a: Option<&char>, b: Option<&char>| {
+            matches!((a, b), (Some('M'), Some('S')) | (Some('S'), Some('M')))
+        };
+        self.grid
+            .find_all(&'A')
+            .filter(|&pos| {
+                let at = |step| self.grid.offset(pos, step).map(|p| &self.grid[p]);
+                is_mas(at((-1, -1)), at((1, 1))) && is_mas(at((-1, 1)), at((1, -1)))
+            })
+            .count()
     }
+}
 
-    pub fn find_cross_mas(&self) -> usize {
-        let dim = self.char_vec.len();
-        let mut count = 0;
-        for row in 0..dim - 2 {
-            for col in 0..dim - 2 {
-                let a = [(row, col), (row, col + 1), (row, col + 2)];
-                let b = [(row + 1, col), (row + 1, col + 1), (row + 1, col + 2)];
-                let c = [(row + 2, col), (row + 2, col + 1), (row + 2, col + 2)];
-
-                let a_cstr: Vec<char> = a
-                    .iter()
-                    .map(|coords| self.char_vec[coords.0][coords.1])
-                    .collect();
-                let b_cstr: Vec<char> = b
-                    .iter()
-                    .map(|coords| self.char_vec[coords.0][coords.1])
-                    .collect();
-                let c_cstr: Vec<char> = c
-                    .iter()
-                    .map(|coords| self.char_vec[coords.0][coords.1])
-                    .collect();
-
-                let mat = vec![a_cstr.clone(), b_cstr.clone(), c_cstr.clone()];
-                let pp = diagonal_pos_pos(&mat);
-                let pn = diagonal_pos_neg(&mat);
-
-                let pns: String = pn[2].iter().map(|c| **c).collect();
-                let pps: String = pp[2].iter().map(|c| **c).collect();
-
-                if (pns.as_str() == "SAM" || pns.as_str() == "MAS")
-                    && (pps.as_str() == "SAM" || pps.as_str() == "MAS")
-                {
-                    count += 1
-                }
-            }
-        }
-        count
+#[derive(Default)]
+pub struct Day4Solution {
+    d4: Option<Day4>,
+    d4b: Option<Day4PartB>,
+}
+
+impl Solution for Day4Solution {
+    fn day(&self) -> u32 {
+        4
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let raw = input.to_string();
+        let mut d4 = Day4::new(&raw);
+        d4.make_all_strings()?;
+        self.d4 = Some(d4);
+        self.d4b = Some(Day4PartB::new(&raw)?);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d4 = self.d4.as_ref()?;
+        Some(d4.count_xmas().to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d4b = self.d4b.as_ref()?;
+        Some(d4b.find_cross_mas().to_string())
     }
 }
 
 fn main() {
-    let aoc: AocHelper = AocHelper::new(
-        4,
-        Some(vec!["second_test".to_string(), "p2_input".to_string()]),
-    );
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-    let second_test_input = aoc
-        .get_input_as_string(RequestedAocInputType::CustomTest {
-            fname: "second_test".to_string(),
-        })
-        .unwrap();
-
-    let p2_input = aoc
-        .get_input_as_string(RequestedAocInputType::CustomTest {
-            fname: "p2_input".to_string(),
-        })
-        .unwrap();
-
-    let mut d4 = Day4::new(&test_input);
-    d4.make_all_strings();
-    debug!("All strings: {:?}", d4.all_strings);
-    let count = d4.count_xmas();
-    info!("Count: {}", count);
-
-    let mut d4 = Day4::new(&second_test_input);
-    d4.make_all_strings();
-    debug!("All strings: {:?}", d4.all_strings);
-    let count = d4.count_xmas();
-    info!("Count: {}", count);
-
-    let mut d4 = Day4::new(&real_input);
-    d4.make_all_strings();
-    debug!("All strings: {:?}", d4.all_strings);
-    let count = d4.count_xmas();
-    info!("Count: {}", count);
-
-    let mut d4b = Day4PartB::new(&p2_input);
-    d4b.to_vv();
-    debug!("vv: {:?}", d4b.char_vec);
-    let c = d4b.find_cross_mas();
-    info!("Cross count: {}", c);
-
-    let mut d4b = Day4PartB::new(&real_input);
-    d4b.to_vv();
-    debug!("vv: {:?}", d4b.char_vec);
-    let c = d4b.find_cross_mas();
-    info!("Cross count: {}", c);
+    run_standalone::<Day4Solution>();
 }
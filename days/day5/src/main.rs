@@ -1,16 +1,14 @@
-use std::{
-    collections::{BTreeMap, VecDeque},
-    ops::Div,
-    usize,
-};
+use std::{collections::VecDeque, ops::Div, usize};
 
-use aoc2024::{AocHelper, RequestedAocInputType};
+use aoc2024::{
+    multimap::BTreeMultiMap, parse::Span, runner::run_standalone, solution::Solution, AocResult,
+};
 use tracing::{debug, info};
 
 struct Day5 {
     raw: String,
-    deps_list: BTreeMap<i32, Vec<i32>>,
-    anti_deps_list: BTreeMap<i32, Vec<i32>>,
+    deps_list: BTreeMultiMap<i32, Vec<i32>>,
+    anti_deps_list: BTreeMultiMap<i32, Vec<i32>>,
     print_list: Vec<Vec<i32>>,
 }
 
@@ -18,45 +16,23 @@ impl Day5 {
     pub fn new(s: &String) -> Self {
         Self {
             raw: s.clone(),
-            deps_list: BTreeMap::new(),
-            anti_deps_list: BTreeMap::new(),
+            deps_list: BTreeMultiMap::new(),
+            anti_deps_list: BTreeMultiMap::new(),
             print_list: Vec::new(),
         }
     }
 
-    fn append_to_mapping(btm: &mut BTreeMap<i32, Vec<i32>>, k: i32, v: i32) {
-        match btm.get_mut(&k) {
-            Some(vv) => vv.push(v),
-            None => {
-                let mut new_vec = Vec::new();
-                new_vec.push(v);
-                btm.insert(k, new_vec);
-            }
+    pub fn parse(&mut self) -> AocResult<()> {
+        let [rules, updates] = Span::new(&self.raw).sections_n()?;
+        for rule in rules.lines() {
+            let (a, b) = rule.pair("|")?;
+            self.deps_list.insert(b, a);
         }
-    }
-
-    pub fn parse(&mut self) {
-        let lines: Vec<&str> = self.raw.lines().collect();
-        let mut is_split = false;
-        for l in lines {
-            if l.trim().len() == 0 {
-                is_split = true;
-                continue;
-            }
-            if !is_split {
-                let parts: Vec<&str> = l.split('|').collect();
-                let a = parts[0].parse::<i32>().unwrap();
-                let b = parts[1].parse::<i32>().unwrap();
-                Day5::append_to_mapping(&mut self.deps_list, b, a);
-                Day5::append_to_mapping(&mut self.anti_deps_list, a, b);
-            } else {
-                let mut print_set = Vec::new();
-                for num in l.split(',') {
-                    print_set.push(num.parse::<i32>().unwrap());
-                }
-                self.print_list.push(print_set);
-            }
+        for update in updates.lines() {
+            self.print_list.push(update.parse_split(",")?);
         }
+        self.anti_deps_list = self.deps_list.invert();
+        Ok(())
     }
 
     fn is_valid_print(&self, v: &Vec<i32>) -> bool {
@@ -95,12 +71,11 @@ impl Day5 {
 
     fn correct_order(&self, v: &mut Vec<i32>) -> VecDeque<i32> {
         let mut answer: VecDeque<i32> = VecDeque::new();
-        let empty_vec: Vec<i32> = Vec::new();
 
         for (_, val) in v.iter().enumerate() {
             // get all deps and anti deps for val
-            let deps = self.deps_list.get(val).unwrap_or(&empty_vec);
-            let anti_deps = self.anti_deps_list.get(val).unwrap_or(&empty_vec);
+            let deps = self.deps_list.get_or_empty(val);
+            let anti_deps = self.anti_deps_list.get_or_empty(val);
 
             if answer.len() == 0 {
                 debug!("Empty answer, pushed {val} to back");
@@ -165,40 +140,34 @@ impl Day5 {
     }
 }
 
+#[derive(Default)]
+pub struct Day5Solution {
+    d5: Option<Day5>,
+}
+
+impl Solution for Day5Solution {
+    fn day(&self) -> u32 {
+        5
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d5 = Day5::new(&input.to_string());
+        d5.parse()?;
+        self.d5 = Some(d5);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d5 = self.d5.as_ref()?;
+        Some(d5.find_valid_middle_sum().to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d5 = self.d5.as_ref()?;
+        Some(d5.fix_em_all().to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(5, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d5 = Day5::new(&test_input);
-    d5.parse();
-    debug!("Deps: {:?}", d5.deps_list);
-    debug!("Prints: {:?}", d5.print_list);
-    let s = d5.find_valid_middle_sum();
-    info!("Found answer: {s}");
-
-    let mut d5 = Day5::new(&real_input);
-    d5.parse();
-    debug!("Deps: {:?}", d5.deps_list);
-    debug!("Prints: {:?}", d5.print_list);
-    let s = d5.find_valid_middle_sum();
-    info!("Found answer: {s}");
-
-    let mut d5 = Day5::new(&test_input);
-    d5.parse();
-    debug!("Deps: {:?}", d5.deps_list);
-    debug!("Prints: {:?}", d5.print_list);
-    let fixed_ans = d5.fix_em_all();
-    info!("Found ans: {fixed_ans}");
-
-    let mut d5 = Day5::new(&real_input);
-    d5.parse();
-    debug!("Deps: {:?}", d5.deps_list);
-    debug!("Prints: {:?}", d5.print_list);
-    let fixed_ans = d5.fix_em_all();
-    info!("Found ans: {fixed_ans}");
+    run_standalone::<Day5Solution>();
 }
@@ -1,23 +1,17 @@
 use std::collections::BTreeSet;
 
-use aoc2024::{AocHelper, RequestedAocInputType};
+use aoc2024::{
+    geom::Direction, runner::run_standalone, solution::Solution, AocHelperError, AocResult,
+};
 use rayon::prelude::*;
 use tracing::{debug, info};
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
-enum Orientation {
-    North,
-    South,
-    East,
-    West,
-}
-
 #[derive(Debug)]
 struct Day6 {
     raw: String,
     pub obstacles: BTreeSet<(usize, usize)>,
     pub start_pos: (usize, usize),
-    pub start_orientation: Option<Orientation>,
+    pub start_orientation: Option<Direction>,
     pub dimensions: (usize, usize),
 }
 
@@ -32,7 +26,7 @@ impl Day6 {
         }
     }
 
-    pub fn parse(&mut self) {
+    pub fn parse(&mut self) -> AocResult<()> {
         let lines = self.raw.lines();
         let mut row_max: usize = 0;
         let mut col_max: usize = 0;
@@ -42,29 +36,26 @@ impl Day6 {
                     '#' => {
                         self.obstacles.insert((row, col));
                     }
-                    '<' => {
-                        self.start_pos = (row, col);
-                        self.start_orientation = Some(Orientation::West);
-                    }
-                    '^' => {
-                        self.start_pos = (row, col);
-                        self.start_orientation = Some(Orientation::North);
-                    }
-                    'v' => {
-                        self.start_pos = (row, col);
-                        self.start_orientation = Some(Orientation::South);
+                    c => {
+                        if let Some(dir) = Direction::from_char(c) {
+                            self.start_pos = (row, col);
+                            self.start_orientation = Some(dir);
+                        }
                     }
-                    '>' => {
-                        self.start_pos = (row, col);
-                        self.start_orientation = Some(Orientation::East);
-                    }
-                    _ => {}
                 }
                 col_max = col;
             }
             row_max = row;
         }
         self.dimensions = (row_max + 1, col_max + 1);
+        if self.start_orientation.is_none() {
+            return Err(AocHelperError::parse_error(
+                1,
+                1,
+                "no guard (^v<>) in the map",
+            ));
+        }
+        Ok(())
     }
 
     pub fn walk_the_pattern(&self) -> usize {
@@ -78,26 +69,8 @@ impl Day6 {
         let mut naive_set: BTreeSet<(usize, usize)> = BTreeSet::new();
         while in_range(cur_pos, self.dimensions) {
             debug!("Currently at {:?} facing {:?}", cur_pos, cur_orientation);
-            let mut next_pos = cur_pos;
-            let next_orientation: Orientation;
-            match cur_orientation {
-                Orientation::North => {
-                    next_pos.0 -= 1;
-                    next_orientation = Orientation::East;
-                }
-                Orientation::South => {
-                    next_pos.0 += 1;
-                    next_orientation = Orientation::West;
-                }
-                Orientation::East => {
-                    next_pos.1 += 1;
-                    next_orientation = Orientation::South;
-                }
-                Orientation::West => {
-                    next_pos.1 -= 1;
-                    next_orientation = Orientation::North;
-                }
-            }
+            let next_pos = step(cur_pos, cur_orientation);
+            let next_orientation = cur_orientation.turn_right();
 
             if self.obstacles.contains(&next_pos) {
                 debug!(
@@ -117,11 +90,21 @@ impl Day6 {
     }
 }
 
+/// The guard only walks while `in_range`, which keeps it off row and column
+/// 0, so this never steps below zero.
+fn step(pos: (usize, usize), dir: Direction) -> (usize, usize) {
+    let delta = dir.delta::<isize>();
+    (
+        pos.0.wrapping_add_signed(delta.row),
+        pos.1.wrapping_add_signed(delta.col),
+    )
+}
+
 fn find_a_loop(
     start_pos: (usize, usize),
     test_pos: (usize, usize),
     dimensions: (usize, usize),
-    start_orientation: Orientation,
+    start_orientation: Direction,
     obstacles: &BTreeSet<(usize, usize)>,
 ) -> bool {
     let mut cur_pos = start_pos;
@@ -131,29 +114,11 @@ fn find_a_loop(
         p.0 > 0 && p.1 > 0 && p.0 < q.0 && p.1 < q.1
     }
 
-    let mut less_naive_set: BTreeSet<(usize, usize, Orientation)> = BTreeSet::new();
+    let mut less_naive_set: BTreeSet<(usize, usize, Direction)> = BTreeSet::new();
     while in_range(cur_pos, dimensions) {
         // debug!("Currently at {:?} facing {:?}", cur_pos, cur_orientation);
-        let mut next_pos = cur_pos;
-        let next_orientation: Orientation;
-        match cur_orientation {
-            Orientation::North => {
-                next_pos.0 -= 1;
-                next_orientation = Orientation::East;
-            }
-            Orientation::South => {
-                next_pos.0 += 1;
-                next_orientation = Orientation::West;
-            }
-            Orientation::East => {
-                next_pos.1 += 1;
-                next_orientation = Orientation::South;
-            }
-            Orientation::West => {
-                next_pos.1 -= 1;
-                next_orientation = Orientation::North;
-            }
-        }
+        let next_pos = step(cur_pos, cur_orientation);
+        let next_orientation = cur_orientation.turn_right();
 
         if obstacles.contains(&next_pos) || next_pos == test_pos {
             // debug!("WILL HIT SOMETHING, TURNING INSTEAD to {:?}", next_orientation);
@@ -196,36 +161,34 @@ fn find_all_loops(day: &Day6) -> Vec<(usize, usize)> {
     ans
 }
 
+#[derive(Default)]
+pub struct Day6Solution {
+    d6: Option<Day6>,
+}
+
+impl Solution for Day6Solution {
+    fn day(&self) -> u32 {
+        6
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d6 = Day6::new(&input.to_string());
+        d6.parse()?;
+        self.d6 = Some(d6);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d6 = self.d6.as_ref()?;
+        Some(d6.walk_the_pattern().to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d6 = self.d6.as_ref()?;
+        Some(find_all_loops(d6).len().to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(6, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d6 = Day6::new(&test_input);
-    d6.parse();
-    debug!("{:?}", d6);
-    let ans = d6.walk_the_pattern();
-    info!("Got answer: {}", ans);
-
-    let mut d6 = Day6::new(&real_input);
-    d6.parse();
-    debug!("{:?}", d6);
-    let ans = d6.walk_the_pattern();
-    info!("Got answer: {}", ans);
-
-    let mut d6 = Day6::new(&test_input);
-    d6.parse();
-    debug!("{:?}", d6);
-    let loops = find_all_loops(&d6);
-    info!("Loops at: {:?}, total: {}", loops, loops.len());
-
-    let mut d6 = Day6::new(&real_input);
-    d6.parse();
-    debug!("{:?}", d6);
-    let loops = find_all_loops(&d6);
-    info!("Loops at: {:?}, total: {}", loops, loops.len());
+    run_standalone::<Day6Solution>();
 }
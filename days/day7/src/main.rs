@@ -1,6 +1,6 @@
 use std::collections::VecDeque;
 
-use aoc2024::{AocHelper, RequestedAocInputType};
+use aoc2024::{parse::Span, runner::run_standalone, solution::Solution, AocResult};
 use tracing::{debug, info};
 
 #[derive(Clone, Debug)]
@@ -127,17 +127,15 @@ impl Day7 {
         }
     }
 
-    pub fn parse(&mut self) {
-        self.raw.lines().into_iter().for_each(|line| {
-            let parts: Vec<&str> = line.split(':').collect();
-            let total = parts[0].parse::<usize>().unwrap();
-            let mut operands: VecDeque<usize> = VecDeque::new();
-            parts[1].split_ascii_whitespace().for_each(|num| {
-                operands.push_back(num.parse::<usize>().unwrap());
-            });
+    pub fn parse(&mut self) -> AocResult<()> {
+        for line in Span::new(&self.raw).lines() {
+            let (total, nums) = line.split_once(":")?;
+            let total: usize = total.parse()?;
+            let operands: VecDeque<usize> = nums.parse_words::<usize>()?.into();
 
             self.equations.push(Equation { total, operands })
-        });
+        }
+        Ok(())
     }
 
     pub fn get_answer(&mut self) -> usize {
@@ -173,38 +171,34 @@ impl Day7 {
     }
 }
 
+#[derive(Default)]
+pub struct Day7Solution {
+    d7: Option<Day7>,
+}
+
+impl Solution for Day7Solution {
+    fn day(&self) -> u32 {
+        7
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d7 = Day7::new(&input.to_string());
+        d7.parse()?;
+        self.d7 = Some(d7);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d7 = self.d7.as_mut()?;
+        Some(d7.get_answer().to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d7 = self.d7.as_mut()?;
+        Some(d7.get_part2_answer().to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(7, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d7: Day7 = Day7::new(&test_input);
-    d7.parse();
-    debug!("Contents: {:?}", d7);
-    let ans = d7.get_answer();
-    info!("Answer: {ans}");
-
-    let mut d7: Day7 = Day7::new(&real_input);
-    d7.parse();
-    // debug!("Contents: {:?}", d7);
-    let ans = d7.get_answer();
-    info!("Answer: {ans}");
-
-    let mut d7: Day7 = Day7::new(&test_input);
-    d7.parse();
-    debug!("Contents: {:?}", d7);
-    let ans = d7.get_part2_answer();
-    info!("Answer: {ans}");
-
-    // return;
-
-    let mut d7: Day7 = Day7::new(&real_input);
-    d7.parse();
-    // debug!("Contents: {:?}", d7);
-    let ans = d7.get_part2_answer();
-    info!("Answer: {ans}");
+    run_standalone::<Day7Solution>();
 }
@@ -1,12 +1,12 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use aoc2024::{AocHelper, RequestedAocInputType};
-use tracing::{debug, info};
+use aoc2024::{multimap::BTreeMultiMap, runner::run_standalone, solution::Solution, AocResult};
+use tracing::debug;
 
 #[derive(Debug)]
 struct Day8 {
     raw: String,
-    antenna_locations: BTreeMap<char, Vec<(i32, i32)>>,
+    antenna_locations: BTreeMultiMap<char, Vec<(i32, i32)>>,
     num_rows: usize,
     num_cols: usize,
     _antinodes: BTreeSet<Point>,
@@ -34,7 +34,7 @@ impl Day8 {
     pub fn new(s: &String) -> Self {
         Self {
             raw: s.clone(),
-            antenna_locations: BTreeMap::new(),
+            antenna_locations: BTreeMultiMap::new(),
             num_cols: 0,
             num_rows: 0,
             _antinodes: BTreeSet::new(),
@@ -42,32 +42,14 @@ impl Day8 {
         }
     }
 
-    // TODO: put this in the library
-    fn append_to_mapping(btm: &mut BTreeMap<char, Vec<(i32, i32)>>, k: char, v: (i32, i32)) {
-        match btm.get_mut(&k) {
-            Some(vv) => {
-                vv.push(v);
-                vv.sort()
-            }
-            None => {
-                let mut new_vec = Vec::new();
-                new_vec.push(v);
-                btm.insert(k, new_vec);
-            }
-        }
-    }
-
     pub fn parse(&mut self) {
         let mut mr = 0;
         let mut mc = 0;
         for (row, line) in self.raw.lines().into_iter().enumerate() {
             for (col, c) in line.chars().enumerate() {
+                // scanning row by row keeps each antenna's locations sorted
                 if c.is_ascii_alphanumeric() {
-                    Day8::append_to_mapping(
-                        &mut self.antenna_locations,
-                        c,
-                        (row as i32, col as i32),
-                    );
+                    self.antenna_locations.insert(c, (row as i32, col as i32));
                 }
                 mc = col;
             }
@@ -201,32 +183,35 @@ impl Day8 {
     }
 }
 
+#[derive(Default)]
+pub struct Day8Solution {
+    d8: Option<Day8>,
+}
+
+impl Solution for Day8Solution {
+    fn day(&self) -> u32 {
+        8
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d8 = Day8::new(&input.to_string());
+        d8.parse();
+        d8.build_frequencies();
+        self.d8 = Some(d8);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d8 = self.d8.as_mut()?;
+        Some(d8.calculate_antinodes().len().to_string())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d8 = self.d8.as_ref()?;
+        Some(d8.get_total_line_points().len().to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(8, None);
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d8: Day8 = Day8::new(&test_input);
-    d8.parse();
-    debug!("d8: {:?}", d8);
-    d8.build_frequencies();
-    debug!("{:?}", d8);
-    let antinodes = d8.calculate_antinodes();
-    let all_lines = d8.get_total_line_points();
-    info!("All antinodes: {:?} len: {}", antinodes, antinodes.len());
-    info!("All lines? {:?} len: {}", all_lines, all_lines.len());
-
-    let mut d8: Day8 = Day8::new(&real_input);
-    d8.parse();
-    debug!("d8: {:?}", d8);
-    d8.build_frequencies();
-    debug!("{:?}", d8);
-    let antinodes = d8.calculate_antinodes();
-    let all_lines = d8.get_total_line_points();
-    info!("All antinodes: {:?} len: {}", antinodes, antinodes.len());
-    info!("All lines? {:?} len: {}", all_lines, all_lines.len());
+    run_standalone::<Day8Solution>();
 }
@@ -1,4 +1,4 @@
-use aoc2024::{AocHelper, RequestedAocInputType};
+use aoc2024::{runner::run_standalone, solution::Solution, AocHelperError, AocResult};
 use tracing::{debug, info};
 
 #[derive(Debug)]
@@ -33,13 +33,19 @@ impl Day9 {
         }
     }
 
-    pub fn parse(&mut self) {
+    pub fn parse(&mut self) -> AocResult<()> {
         self.start_state = self
             .raw
-            .as_bytes()
-            .iter()
-            .map(|b| (b - 0x30) as i64)
-            .collect();
+            .trim_end()
+            .chars()
+            .enumerate()
+            .map(|(col, c)| {
+                c.to_digit(10).map(|d| d as i64).ok_or_else(|| {
+                    AocHelperError::parse_error(1, col + 1, format!("{:?} is not a digit", c))
+                })
+            })
+            .collect::<AocResult<_>>()?;
+        Ok(())
     }
 
     pub fn expand(&mut self) {
@@ -132,35 +138,30 @@ impl Day9 {
     }
 }
 
-fn main() {
-    let aoc: AocHelper = AocHelper::new(9, Some(vec!["second_test".to_string()]));
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let _test2_input = aoc
-        .get_input_as_string(RequestedAocInputType::CustomTest {
-            fname: "second_test".to_string(),
-        })
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
+#[derive(Default)]
+pub struct Day9Solution {
+    d9: Option<Day9>,
+}
 
-    let mut d9 = Day9::new(&test_input);
-    d9.parse();
-    debug!("{:?}", d9);
-    d9.expand();
-    debug!("{:?}", d9);
-    let res = d9.naive_replace();
-    debug!("{:?}", d9);
-    info!("Ans: {res}");
+impl Solution for Day9Solution {
+    fn day(&self) -> u32 {
+        9
+    }
 
-    let mut d9 = Day9::new(&real_input);
-    d9.parse();
-    debug!("{:?}", d9);
-    d9.expand();
-    debug!("{:?}", d9);
-    let res = d9.naive_replace();
-    // debug!("{:?}", d9);
-    info!("Ans: {res}");
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d9 = Day9::new(&input.to_string());
+        d9.parse()?;
+        self.d9 = Some(d9);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        let d9 = self.d9.as_mut()?;
+        d9.expand();
+        Some(d9.naive_replace().to_string())
+    }
+}
+
+fn main() {
+    run_standalone::<Day9Solution>();
 }
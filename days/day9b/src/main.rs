@@ -1,7 +1,7 @@
 use std::collections::{BTreeSet, BinaryHeap};
 
-use aoc2024::{AocHelper, RequestedAocInputType};
-use tracing::{debug, info};
+use aoc2024::{runner::run_standalone, solution::Solution, AocHelperError, AocResult};
+use tracing::debug;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Block {
@@ -60,11 +60,18 @@ impl Day9 {
         }
     }
 
-    pub fn parse(&mut self) {
-        self.raw.chars().for_each(|c| {
-            self.start_state
-                .push(c.to_string().parse::<usize>().unwrap());
-        });
+    pub fn parse(&mut self) -> AocResult<()> {
+        for (col, c) in self.raw.trim_end().chars().enumerate() {
+            let Some(d) = c.to_digit(10) else {
+                return Err(AocHelperError::parse_error(
+                    1,
+                    col + 1,
+                    format!("{:?} is not a digit", c),
+                ));
+            };
+            self.start_state.push(d as usize);
+        }
+        Ok(())
     }
 
     pub fn build_blocks(&mut self) {
@@ -280,28 +287,31 @@ impl Day9 {
     }
 }
 
+#[derive(Default)]
+pub struct Day9Solution {
+    d9: Option<Day9>,
+}
+
+impl Solution for Day9Solution {
+    fn day(&self) -> u32 {
+        9
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        let mut d9 = Day9::new(&input.to_string());
+        d9.parse()?;
+        d9.build_blocks();
+        self.d9 = Some(d9);
+        Ok(())
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        let d9 = self.d9.as_mut()?;
+        d9.defrag();
+        Some(d9.get_score().to_string())
+    }
+}
+
 fn main() {
-    let aoc: AocHelper = AocHelper::new(9, Some(vec!["second_test".to_string()]));
-    let test_input = aoc
-        .get_input_as_string(RequestedAocInputType::Test)
-        .unwrap();
-    let real_input = aoc
-        .get_input_as_string(RequestedAocInputType::Real)
-        .unwrap();
-
-    let mut d9 = Day9::new(&test_input);
-    d9.parse();
-    d9.build_blocks();
-    debug!("{:?}", d9);
-    d9.defrag();
-    let ans = d9.get_score();
-    info!("Answer? {}", ans);
-
-    let mut d9 = Day9::new(&real_input);
-    d9.parse();
-    d9.build_blocks();
-    debug!("{:?}", d9);
-    d9.defrag();
-    let ans = d9.get_score();
-    info!("Answer? {}", ans);
+    run_standalone::<Day9Solution>();
 }
@@ -0,0 +1,194 @@
+use core::fmt;
+use std::collections::HashMap;
+use std::path::Path;
+
+use tracing::{debug, error};
+
+use crate::solution::Part;
+
+/// Expected answers for one day, loaded from `dayN/answers.toml`:
+///
+/// ```toml
+/// [test_input]
+/// part1 = 11
+/// part2 = 31
+///
+/// [real_input]
+/// part1 = "1234"
+/// ```
+///
+/// Tables are keyed by input file name, values may be strings or integers.
+#[derive(Debug, Clone, Default)]
+pub struct Answers {
+    inner: HashMap<String, HashMap<Part, String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerStatus {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl fmt::Display for AnswerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswerStatus::Pass => write!(f, "pass"),
+            AnswerStatus::Fail { expected } => write!(f, "FAIL, expected {}", expected),
+            AnswerStatus::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl Answers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A missing file just means nothing is known yet.
+    pub fn load(path: &Path) -> Self {
+        let Ok(raw) = std::fs::read_to_string(path) else {
+            debug!("No answers file at {:?}", path);
+            return Self::new();
+        };
+        match Answers::parse(&raw) {
+            Ok(answers) => answers,
+            Err(e) => {
+                error!("Failed to parse answers file {:?}: {}", path, e);
+                Self::new()
+            }
+        }
+    }
+
+    pub fn parse(raw: &str) -> Result<Self, toml::de::Error> {
+        let table: toml::Table = raw.parse()?;
+        let mut answers = Answers::new();
+        for (input_name, parts) in table.iter() {
+            let Some(parts) = parts.as_table() else {
+                continue;
+            };
+            for part in Part::ALL {
+                let key = format!("part{}", part.number());
+                let expected = match parts.get(&key) {
+                    Some(toml::Value::String(s)) => s.clone(),
+                    Some(toml::Value::Integer(i)) => i.to_string(),
+                    Some(other) => other.to_string(),
+                    None => continue,
+                };
+                answers.insert(input_name, part, &expected);
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn insert(&mut self, input_name: &str, part: Part, expected: &str) {
+        self.inner
+            .entry(input_name.to_string())
+            .or_default()
+            .insert(part, expected.to_string());
+    }
+
+    /// True when no answer is recorded for any input.
+    pub fn is_empty(&self) -> bool {
+        self.inner.values().all(|parts| parts.is_empty())
+    }
+
+    pub fn get(&self, input_name: &str, part: Part) -> Option<&str> {
+        self.inner.get(input_name)?.get(&part).map(|s| s.as_str())
+    }
+
+    pub fn check(&self, input_name: &str, part: Part, answer: &str) -> AnswerStatus {
+        match self.get(input_name, part) {
+            Some(expected) if expected == answer.trim() => AnswerStatus::Pass,
+            Some(expected) => AnswerStatus::Fail {
+                expected: expected.to_string(),
+            },
+            None => AnswerStatus::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = r#"
+[test_input]
+part1 = 11
+part2 = "co,de,ka,ta"
+
+[real_input]
+part1 = "1234"
+"#;
+
+    #[test]
+    fn integers_and_strings() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(answers.get("test_input", Part::One), Some("11"));
+        assert_eq!(answers.get("test_input", Part::Two), Some("co,de,ka,ta"));
+        assert_eq!(answers.get("real_input", Part::One), Some("1234"));
+        assert_eq!(
+            answers.check("test_input", Part::One, "11\n"),
+            AnswerStatus::Pass
+        );
+        assert_eq!(
+            answers.check("real_input", Part::One, "1234"),
+            AnswerStatus::Pass
+        );
+    }
+
+    #[test]
+    fn missing_sections_and_parts_are_unknown() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(answers.get("real_input", Part::Two), None);
+        assert_eq!(answers.get("test_input2", Part::One), None);
+        assert_eq!(
+            answers.check("real_input", Part::Two, "1"),
+            AnswerStatus::Unknown
+        );
+        assert_eq!(
+            answers.check("test_input2", Part::One, "1"),
+            AnswerStatus::Unknown
+        );
+        assert_eq!(
+            Answers::new().check("test_input", Part::One, "11"),
+            AnswerStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn mismatches_report_the_expected_answer() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        let status = answers.check("test_input", Part::One, "12");
+        assert_eq!(
+            status,
+            AnswerStatus::Fail {
+                expected: "11".to_string()
+            }
+        );
+        assert_eq!(status.to_string(), "FAIL, expected 11");
+        assert_eq!(AnswerStatus::Pass.to_string(), "pass");
+    }
+
+    #[test]
+    fn malformed_toml_is_an_error() {
+        assert!(Answers::parse("[test_input\npart1 = 11").is_err());
+        assert!(Answers::parse("[test_input]\npart1 = ").is_err());
+        // not a table of parts, nothing to learn from it
+        let answers = Answers::parse("test_input = 11").unwrap();
+        assert_eq!(answers.get("test_input", Part::One), None);
+    }
+
+    #[test]
+    fn load_tolerates_missing_and_broken_files() {
+        let dir = std::env::temp_dir().join(format!("aoc2024-answers-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let broken = dir.join("answers.toml");
+        std::fs::write(&broken, "[test_input\n").unwrap();
+        let from_broken = Answers::load(&broken);
+        let from_missing = Answers::load(&dir.join("nope.toml"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(from_broken.inner.is_empty());
+        assert!(from_missing.inner.is_empty());
+    }
+}
@@ -0,0 +1,405 @@
+use core::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use tracing::info;
+
+use crate::runner::{Registry, SolutionFactory};
+use crate::solution::Part;
+use crate::timeout::run_catching_panics;
+use crate::{AocHelper, AocHelperError, AocResult, RequestedAocInputType};
+
+/// min/median/mean/max over a set of runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// `None` for an empty sample.
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        let median = match n {
+            0 => return None,
+            n if n % 2 == 1 => sorted[n / 2],
+            n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+        };
+        Some(Stats {
+            runs: n,
+            min: sorted[0],
+            median,
+            mean: sorted.iter().sum::<Duration>() / n as u32,
+            max: sorted[n - 1],
+        })
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {:.2?} / median {:.2?} / max {:.2?}",
+            self.min, self.median, self.max
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Timings {
+    pub answer: String,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Timings for one (day, part, input). Parse is timed on its own since the
+/// runner parses again for every part.
+#[derive(Debug)]
+pub struct BenchResult {
+    pub day: u32,
+    pub part: Part,
+    pub input: RequestedAocInputType,
+    /// `Ok(None)` means nothing is registered for this part.
+    pub timings: AocResult<Option<Timings>>,
+}
+
+impl fmt::Display for BenchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {} {} [{}]: ", self.day, self.part, self.input)?;
+        match &self.timings {
+            Ok(Some(t)) => write!(
+                f,
+                "parse {}, solve {} ({} runs)",
+                t.parse, t.solve, t.solve.runs
+            ),
+            Ok(None) => write!(f, "not implemented"),
+            Err(e) => write!(f, "error: {}", e),
+        }
+    }
+}
+
+/// Parses and solves `part` once, returning the answer with the time each step took.
+fn time_once(
+    factory: SolutionFactory,
+    part: Part,
+    input_type: &RequestedAocInputType,
+    input: &str,
+) -> AocResult<(Option<String>, Duration, Duration)> {
+    let mut solution = factory();
+    solution.configure(input_type);
+    let start = Instant::now();
+    solution.parse(input)?;
+    let parsed = Instant::now();
+    let answer = solution.solve(part);
+    Ok((answer, parsed - start, parsed.elapsed()))
+}
+
+/// Runs `part` `runs` times, each on a fresh solution, under the registry's
+/// time budget for the whole batch. A panic comes back as `SolutionPanicked`.
+pub fn bench_part(
+    aoc: &AocHelper,
+    registry: &Registry,
+    day: u32,
+    part: Part,
+    input_type: &RequestedAocInputType,
+    runs: usize,
+) -> AocResult<Option<Timings>> {
+    let Some(factory) = registry.get(day, part) else {
+        return Ok(None);
+    };
+    let input = aoc.get_input_as_string(input_type.clone())?;
+    let input_type = input_type.clone();
+    let runs = runs.max(1);
+    run_catching_panics(registry.timeouts().budget(day, part), move || {
+        let mut parse_samples = Vec::with_capacity(runs);
+        let mut solve_samples = Vec::with_capacity(runs);
+        let mut answer = None;
+        for _ in 0..runs {
+            let (a, parse, solve) = time_once(factory, part, &input_type, &input)?;
+            parse_samples.push(parse);
+            solve_samples.push(solve);
+            answer = a;
+        }
+        let Some(answer) = answer else {
+            return Ok(None);
+        };
+        Ok(Some(Timings {
+            answer,
+            // runs is at least 1, so neither sample is empty
+            parse: Stats::from_samples(&parse_samples).unwrap(),
+            solve: Stats::from_samples(&solve_samples).unwrap(),
+        }))
+    })
+}
+
+/// Same shape as `runner::run_day`: parts first, then inputs.
+pub fn bench_day(
+    registry: &Registry,
+    day: u32,
+    parts: &[Part],
+    inputs: &[RequestedAocInputType],
+    runs: usize,
+) -> AocResult<Vec<BenchResult>> {
+    let custom_inputs: Vec<String> = inputs
+        .iter()
+        .filter_map(|input| match input {
+            RequestedAocInputType::CustomTest { fname } => Some(fname.clone()),
+            _ => None,
+        })
+        .collect();
+    let aoc = AocHelper::new(day, Some(custom_inputs))?;
+
+    let mut results = Vec::new();
+    for part in parts {
+        for input in inputs {
+            info!("Benchmarking day {} {} [{}]", day, part, input);
+            results.push(BenchResult {
+                day,
+                part: *part,
+                input: input.clone(),
+                timings: bench_part(&aoc, registry, day, *part, input, runs),
+            });
+        }
+    }
+    Ok(results)
+}
+
+/// A whole benchmark run, written out as JSON or CSV. Durations are in
+/// nanoseconds.
+#[derive(Debug, Default)]
+pub struct BenchReport {
+    pub results: Vec<BenchResult>,
+}
+
+const CSV_HEADER: &str = "day,part,input,runs,parse_min_ns,parse_median_ns,parse_mean_ns,parse_max_ns,solve_min_ns,solve_median_ns,solve_mean_ns,solve_max_ns,error";
+
+impl BenchReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn extend(&mut self, results: Vec<BenchResult>) {
+        self.results.extend(results);
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(CSV_HEADER);
+        out.push('\n');
+        for r in &self.results {
+            let prefix = format!(
+                "{},{},{}",
+                r.day,
+                r.part.number(),
+                csv_field(&r.input.to_string())
+            );
+            let line = match &r.timings {
+                Ok(Some(t)) => format!(
+                    "{},{},{},{},{},{},{},{},{},{},",
+                    prefix,
+                    t.solve.runs,
+                    t.parse.min.as_nanos(),
+                    t.parse.median.as_nanos(),
+                    t.parse.mean.as_nanos(),
+                    t.parse.max.as_nanos(),
+                    t.solve.min.as_nanos(),
+                    t.solve.median.as_nanos(),
+                    t.solve.mean.as_nanos(),
+                    t.solve.max.as_nanos(),
+                ),
+                Ok(None) => continue,
+                Err(e) => format!("{},,,,,,,,,,{}", prefix, csv_field(&e.to_string())),
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let entries: Vec<String> = self
+            .results
+            .iter()
+            .filter_map(|r| {
+                let prefix = format!(
+                    "\"day\": {}, \"part\": {}, \"input\": {}",
+                    r.day,
+                    r.part.number(),
+                    json_string(&r.input.to_string())
+                );
+                match &r.timings {
+                    Ok(Some(t)) => Some(format!(
+                        "{{{}, \"answer\": {}, \"parse\": {}, \"solve\": {}}}",
+                        prefix,
+                        json_string(&t.answer),
+                        json_stats(&t.parse),
+                        json_stats(&t.solve)
+                    )),
+                    Ok(None) => None,
+                    Err(e) => Some(format!(
+                        "{{{}, \"error\": {}}}",
+                        prefix,
+                        json_string(&e.to_string())
+                    )),
+                }
+            })
+            .collect();
+        format!("[\n  {}\n]\n", entries.join(",\n  "))
+    }
+
+    /// Writes CSV for a `.csv` path and JSON for anything else.
+    pub fn write(&self, path: &Path) -> AocResult<()> {
+        let contents = match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => self.to_csv(),
+            _ => self.to_json(),
+        };
+        fs::write(path, contents).map_err(|source| AocHelperError::FileWriteError {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+fn json_stats(stats: &Stats) -> String {
+    format!(
+        "{{\"runs\": {}, \"min_ns\": {}, \"median_ns\": {}, \"mean_ns\": {}, \"max_ns\": {}}}",
+        stats.runs,
+        stats.min.as_nanos(),
+        stats.median.as_nanos(),
+        stats.mean.as_nanos(),
+        stats.max.as_nanos()
+    )
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn report() -> BenchReport {
+        let stats = |min, median, mean, max| Stats {
+            runs: 3,
+            min: ms(min),
+            median: ms(median),
+            mean: ms(mean),
+            max: ms(max),
+        };
+        let mut report = BenchReport::new();
+        report.extend(vec![
+            BenchResult {
+                day: 1,
+                part: Part::One,
+                input: RequestedAocInputType::Test,
+                timings: Ok(Some(Timings {
+                    answer: "11".to_string(),
+                    parse: stats(1, 2, 2, 3),
+                    solve: stats(4, 5, 6, 9),
+                })),
+            },
+            BenchResult {
+                day: 1,
+                part: Part::Two,
+                input: RequestedAocInputType::Test,
+                timings: Ok(None),
+            },
+            BenchResult {
+                day: 2,
+                part: Part::One,
+                input: RequestedAocInputType::CustomTest {
+                    fname: "test_input2".to_string(),
+                },
+                timings: Err(AocHelperError::SolutionPanicked {
+                    message: "bad \"input\", line 3".to_string(),
+                }),
+            },
+        ]);
+        report
+    }
+
+    #[test]
+    fn stats_of_an_odd_sample() {
+        let stats = Stats::from_samples(&[ms(9), ms(1), ms(5)]).unwrap();
+        assert_eq!(stats.runs, 3);
+        assert_eq!((stats.min, stats.median, stats.max), (ms(1), ms(5), ms(9)));
+        assert_eq!(stats.mean, ms(5));
+    }
+
+    #[test]
+    fn even_samples_take_the_middle_pair() {
+        let stats = Stats::from_samples(&[ms(8), ms(2), ms(4), ms(1)]).unwrap();
+        assert_eq!(stats.median, ms(3));
+        assert_eq!(stats.mean, Duration::from_micros(3750));
+        assert_eq!((stats.min, stats.max), (ms(1), ms(8)));
+    }
+
+    #[test]
+    fn a_single_sample_is_everything() {
+        let stats = Stats::from_samples(&[ms(7)]).unwrap();
+        assert_eq!(
+            stats,
+            Stats {
+                runs: 1,
+                min: ms(7),
+                median: ms(7),
+                mean: ms(7),
+                max: ms(7),
+            }
+        );
+    }
+
+    #[test]
+    fn no_samples_no_stats() {
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn csv_report() {
+        let expected = format!(
+            "{}\n1,1,test,3,1000000,2000000,2000000,3000000,4000000,5000000,6000000,9000000,\n\
+             2,1,test_input2,,,,,,,,,,\"Solution panicked: bad \"\"input\"\", line 3\"\n",
+            CSV_HEADER
+        );
+        assert_eq!(report().to_csv(), expected);
+    }
+
+    #[test]
+    fn json_report() {
+        let expected = r#"[
+  {"day": 1, "part": 1, "input": "test", "answer": "11", "parse": {"runs": 3, "min_ns": 1000000, "median_ns": 2000000, "mean_ns": 2000000, "max_ns": 3000000}, "solve": {"runs": 3, "min_ns": 4000000, "median_ns": 5000000, "mean_ns": 6000000, "max_ns": 9000000}},
+  {"day": 2, "part": 1, "input": "test_input2", "error": "Solution panicked: bad \"input\", line 3"}
+]
+"#;
+        assert_eq!(report().to_json(), expected);
+    }
+}
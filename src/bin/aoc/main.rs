@@ -0,0 +1,254 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use aoc2024::answers::AnswerStatus;
+use aoc2024::bench::{bench_day, BenchReport};
+use aoc2024::runner::run_day;
+use aoc2024::scaffold::DayScaffold;
+use aoc2024::solution::Part;
+use aoc2024::{AocHelper, RequestedAocInputType};
+use clap::{Args, Parser, Subcommand};
+
+mod registry;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2024 runner")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a single day, or every registered day with `all`
+    Run {
+        /// Day number, or `all`
+        #[arg(value_parser = parse_day_selection)]
+        day: DaySelection,
+
+        /// Only run this part (1 or 2)
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: Option<u32>,
+
+        /// Runs every `test_input*` file and `real` when no input is given
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Time parse and each part over several runs and report min/median/max
+    Bench {
+        /// Day number, or `all`
+        #[arg(value_parser = parse_day_selection)]
+        day: DaySelection,
+
+        /// Only run this part (1 or 2)
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: Option<u32>,
+
+        /// Uses `real` when no input is given
+        #[command(flatten)]
+        input: InputArgs,
+
+        /// How many times to run each part
+        #[arg(long, default_value_t = 10)]
+        runs: usize,
+
+        /// Write the report here, CSV for a `.csv` path and JSON otherwise
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
+    /// List the registered days and which of their inputs exist
+    List,
+    /// Generate a new day's crate and input files and register it
+    NewDay {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+
+        /// Workspace to add the day to, defaults to the one `aoc` was built from
+        #[arg(long, value_name = "PATH")]
+        workspace: Option<PathBuf>,
+    },
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// `test`, `real`, the name of an extra test file in the day's directory,
+    /// or `-` for stdin
+    #[arg(long, conflicts_with_all = ["stdin", "file"])]
+    input: Option<RequestedAocInputType>,
+
+    /// Read the input from stdin
+    #[arg(long, conflicts_with = "file")]
+    stdin: bool,
+
+    /// Read the input from this file instead of the day's directory
+    #[arg(long, value_name = "PATH")]
+    file: Option<PathBuf>,
+}
+
+impl InputArgs {
+    fn selected(self) -> Option<RequestedAocInputType> {
+        if self.stdin {
+            return Some(RequestedAocInputType::Stdin);
+        }
+        if let Some(path) = self.file {
+            return Some(RequestedAocInputType::Path(path));
+        }
+        self.input
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DaySelection {
+    All,
+    Day(u32),
+}
+
+fn parse_day_selection(s: &str) -> Result<DaySelection, String> {
+    if s == "all" {
+        return Ok(DaySelection::All);
+    }
+    s.parse::<u32>()
+        .map(DaySelection::Day)
+        .map_err(|_| format!("expected a day number or `all`, got `{}`", s))
+}
+
+fn select_days(registry: &aoc2024::runner::Registry, day: DaySelection) -> Option<Vec<u32>> {
+    match day {
+        DaySelection::All => Some(registry.days().collect()),
+        DaySelection::Day(day) if registry.contains_day(day) => Some(vec![day]),
+        DaySelection::Day(day) => {
+            eprintln!("day {} is not registered", day);
+            None
+        }
+    }
+}
+
+fn select_parts(part: Option<u32>) -> Vec<Part> {
+    match part.and_then(Part::from_number) {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let registry = registry::registry();
+
+    match cli.command {
+        Command::NewDay { day, workspace } => {
+            let workspace =
+                workspace.unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+            // a fresh checkout may not have an inputs directory yet
+            let inputs = AocHelper::find_base_path(None).unwrap_or(workspace.join("static"));
+            let created = DayScaffold::new(day, &workspace, &inputs).and_then(|s| s.create());
+            match created {
+                Ok(paths) => {
+                    for path in paths {
+                        println!("created {}", path.display());
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Command::List => {
+            for day in registry.days() {
+                let aoc = match AocHelper::new(day, None) {
+                    Ok(aoc) => aoc,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        return ExitCode::FAILURE;
+                    }
+                };
+                let inputs: Vec<String> = aoc
+                    .available_inputs()
+                    .iter()
+                    .map(|input| input.to_string())
+                    .collect();
+                if inputs.is_empty() {
+                    println!("day {}: no inputs", day);
+                } else {
+                    println!("day {}: {}", day, inputs.join(", "));
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Run { day, part, input } => {
+            let Some(days) = select_days(&registry, day) else {
+                return ExitCode::FAILURE;
+            };
+            let parts = select_parts(part);
+            let inputs: Option<Vec<RequestedAocInputType>> =
+                input.selected().map(|input| vec![input]);
+
+            let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+            for day in days {
+                let results = match run_day(&registry, day, &parts, inputs.as_deref()) {
+                    Ok(results) => results,
+                    Err(e) => {
+                        eprintln!("day {}: {}", day, e);
+                        failed += 1;
+                        continue;
+                    }
+                };
+                for result in results {
+                    println!("{}", result);
+                    if result.is_failure() {
+                        failed += 1;
+                    } else if result.status == Some(AnswerStatus::Pass) {
+                        passed += 1;
+                    } else {
+                        unknown += 1;
+                    }
+                }
+            }
+            println!("{} passed, {} failed, {} unknown", passed, failed, unknown);
+
+            if failed > 0 {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+        Command::Bench {
+            day,
+            part,
+            input,
+            runs,
+            report,
+        } => {
+            let Some(days) = select_days(&registry, day) else {
+                return ExitCode::FAILURE;
+            };
+            let parts = select_parts(part);
+            let inputs = vec![input.selected().unwrap_or(RequestedAocInputType::Real)];
+
+            let mut bench_report = BenchReport::new();
+            for day in days {
+                let results = match bench_day(&registry, day, &parts, &inputs, runs) {
+                    Ok(results) => results,
+                    Err(e) => {
+                        eprintln!("day {}: {}", day, e);
+                        return ExitCode::FAILURE;
+                    }
+                };
+                for result in &results {
+                    println!("{}", result);
+                }
+                bench_report.extend(results);
+            }
+
+            if let Some(path) = report {
+                if let Err(e) = bench_report.write(&path) {
+                    eprintln!("{}", e);
+                    return ExitCode::FAILURE;
+                }
+                println!("report written to {}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Pulls every day's `main.rs` in as a module so the `aoc` binary can
+//! dispatch to them. Each day still builds as its own crate too, where its
+//! own warnings are reported; they are silenced here.
+
+use std::time::Duration;
+
+use aoc2024::runner::Registry;
+use aoc2024::solution::Part;
+
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day1/src/main.rs"]
+mod day1;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day2/src/main.rs"]
+mod day2;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day3/src/main.rs"]
+mod day3;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day4/src/main.rs"]
+mod day4;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day5/src/main.rs"]
+mod day5;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day6/src/main.rs"]
+mod day6;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day7/src/main.rs"]
+mod day7;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day8/src/main.rs"]
+mod day8;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day9/src/main.rs"]
+mod day9;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day9b/src/main.rs"]
+mod day9b;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day10/src/main.rs"]
+mod day10;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day11/src/main.rs"]
+mod day11;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day12/src/main.rs"]
+mod day12;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day13/src/main.rs"]
+mod day13;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day14/src/main.rs"]
+mod day14;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day15/src/main.rs"]
+mod day15;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day16/src/main.rs"]
+mod day16;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day17/src/main.rs"]
+mod day17;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day18/src/main.rs"]
+mod day18;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day19/src/main.rs"]
+mod day19;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day20/src/main.rs"]
+mod day20;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day21/src/main.rs"]
+mod day21;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day22/src/main.rs"]
+mod day22;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day23/src/main.rs"]
+mod day23;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day24/src/main.rs"]
+mod day24;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day24b/src/main.rs"]
+mod day24b;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day25/src/main.rs"]
+mod day25;
+
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<day1::Day1Solution>();
+    registry.register::<day2::Day2Solution>();
+    registry.register::<day3::Day3Solution>();
+    registry.register::<day4::Day4Solution>();
+    registry.register::<day5::Day5Solution>();
+    registry.register::<day6::Day6Solution>();
+    registry.register::<day7::Day7Solution>();
+    registry.register::<day8::Day8Solution>();
+    registry.register::<day9::Day9Solution>();
+    registry.register_part::<day9b::Day9Solution>(Part::Two);
+    registry.register::<day10::Day10Solution>();
+    registry.register::<day11::Day11Solution>();
+    registry.register::<day12::Day12Solution>();
+    registry.register::<day13::Day13Solution>();
+    registry.register::<day14::Day14Solution>();
+    registry.register::<day15::Day15Solution>();
+    registry.register::<day16::Day16Solution>();
+    registry.register::<day17::Day17Solution>();
+    registry.register::<day18::Day18Solution>();
+    registry.register::<day19::Day19Solution>();
+    registry.register::<day20::Day20Solution>();
+    registry.register::<day21::Day21Solution>();
+    registry.register::<day22::Day22Solution>();
+    registry.register::<day23::Day23Solution>();
+    registry.register::<day24::Day24Solution>();
+    registry.register_part::<day24b::Day24Solution>(Part::Two);
+    registry.register::<day25::Day25Solution>();
+
+    // tries an obstacle on every visited tile, slow in debug builds
+    registry.set_timeout(6, Part::Two, Duration::from_secs(300));
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    /// Every day against every input with a recorded answer, see
+    /// `aoc2024::regression`. Passes without checking anything when there
+    /// are no inputs.
+    #[test]
+    fn every_day_matches_its_answers() {
+        aoc2024::regression::check(&super::registry()).assert_passing();
+    }
+}
@@ -0,0 +1,259 @@
+//! Union–find and connected components, for region and connectivity puzzles.
+
+use std::{collections::HashMap, hash::Hash};
+
+use crate::graph::Neighbors;
+
+/// Union–find over the indices `0..len()`, with union by rank and path
+/// compression. New elements can be added as they turn up, which suits
+/// puzzles that join things up one at a time.
+#[derive(Debug, Clone, Default)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    sets: usize,
+}
+
+impl DisjointSet {
+    /// `len` elements, each in a set of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sizes: vec![1; len],
+            sets: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Adds a new element in a set of its own and returns its index.
+    pub fn add(&mut self) -> usize {
+        let idx = self.parents.len();
+        self.parents.push(idx);
+        self.ranks.push(0);
+        self.sizes.push(1);
+        self.sets += 1;
+        idx
+    }
+
+    /// The representative of `x`'s set. Panics if `x` is out of range.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        // point everything on the way straight at the root
+        let mut cur = x;
+        while self.parents[cur] != root {
+            let next = self.parents[cur];
+            self.parents[cur] = root;
+            cur = next;
+        }
+        root
+    }
+
+    /// Merges the sets holding `a` and `b`. Returns `false` if they were
+    /// already one set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.ranks[a] < self.ranks[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parents[small] = big;
+        self.sizes[big] += self.sizes[small];
+        if self.ranks[big] == self.ranks[small] {
+            self.ranks[big] += 1;
+        }
+        self.sets -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The size of the set holding `x`.
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /// How many separate sets there are.
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+}
+
+/// Connected components, numbered `0..len()` in the order their first member
+/// was listed.
+#[derive(Debug, Clone)]
+pub struct Components<T: Hash + Eq + Clone> {
+    ids: HashMap<T, usize>,
+    members: Vec<Vec<T>>,
+}
+
+impl<T: Hash + Eq + Clone> Components<T> {
+    /// The number of components.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// The component `node` is in, `None` if it wasn't one of the nodes.
+    pub fn id(&self, node: &T) -> Option<usize> {
+        self.ids.get(node).copied()
+    }
+
+    /// Whether `a` and `b` are in the same component.
+    pub fn connected(&self, a: &T, b: &T) -> bool {
+        self.id(a).is_some_and(|id| self.id(b) == Some(id))
+    }
+
+    /// The nodes in component `id`, in the order they were listed. Panics if
+    /// there is no such component.
+    pub fn members(&self, id: usize) -> &[T] {
+        &self.members[id]
+    }
+
+    pub fn size(&self, id: usize) -> usize {
+        self.members[id].len()
+    }
+
+    /// Every component's size, by id.
+    pub fn sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.members.iter().map(|m| m.len())
+    }
+
+    /// Every component's members, by id.
+    pub fn iter(&self) -> impl Iterator<Item = &[T]> {
+        self.members.iter().map(|m| m.as_slice())
+    }
+}
+
+/// Splits `nodes` into connected components along `graph`'s edges, taken as
+/// undirected. Neighbours that aren't among `nodes` are ignored.
+pub fn components<T, N>(nodes: impl IntoIterator<Item = T>, graph: &N) -> Components<T>
+where
+    T: Hash + Eq + Clone,
+    N: Neighbors<T> + ?Sized,
+{
+    let mut indices: HashMap<T, usize> = HashMap::new();
+    let mut nodes_by_index: Vec<T> = Vec::new();
+    for node in nodes {
+        if !indices.contains_key(&node) {
+            indices.insert(node.clone(), nodes_by_index.len());
+            nodes_by_index.push(node);
+        }
+    }
+
+    let mut sets = DisjointSet::new(nodes_by_index.len());
+    for (idx, node) in nodes_by_index.iter().enumerate() {
+        for next in graph.neighbors(node) {
+            if let Some(next_idx) = indices.get(&next) {
+                sets.union(idx, *next_idx);
+            }
+        }
+    }
+
+    let mut ids: HashMap<T, usize> = HashMap::new();
+    let mut members: Vec<Vec<T>> = Vec::new();
+    // component id for each set's root, handed out as roots are first seen
+    let mut root_ids: HashMap<usize, usize> = HashMap::new();
+    for (idx, node) in nodes_by_index.into_iter().enumerate() {
+        let root = sets.find(idx);
+        let id = *root_ids.entry(root).or_insert_with(|| {
+            members.push(Vec::new());
+            members.len() - 1
+        });
+        members[id].push(node.clone());
+        ids.insert(node, id);
+    }
+    Components { ids, members }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    #[test]
+    fn unions_track_sets_and_sizes() {
+        let mut sets = DisjointSet::new(6);
+        assert_eq!(sets.set_count(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.same(0, 3));
+        assert!(!sets.same(0, 4));
+        assert_eq!((sets.size_of(2), sets.size_of(5)), (4, 1));
+        assert_eq!(sets.set_count(), 3);
+
+        let added = sets.add();
+        assert_eq!((added, sets.len(), sets.set_count()), (6, 7, 4));
+        sets.union(added, 5);
+        assert_eq!(sets.size_of(5), 2);
+    }
+
+    #[test]
+    fn long_chains_stay_connected() {
+        let mut sets = DisjointSet::new(1000);
+        for idx in 1..1000 {
+            sets.union(idx - 1, idx);
+        }
+        assert_eq!(sets.set_count(), 1);
+        assert_eq!(sets.size_of(0), 1000);
+        assert!(sets.same(0, 999));
+    }
+
+    #[test]
+    fn labels_grid_regions() {
+        let grid = Grid::parse("AAB\nBAB\nBBC", |c| c).unwrap();
+        let regions = grid.components(|a, b| a == b);
+        assert_eq!(regions.len(), 4);
+        assert_eq!(regions.sizes().collect::<Vec<_>>(), [3, 2, 3, 1]);
+        assert_eq!(regions.members(1), [(0, 2), (1, 2)]);
+        assert!(regions.connected(&(1, 0), &(2, 1)));
+        assert!(!regions.connected(&(0, 2), &(1, 0)));
+        assert_eq!(
+            grid.flood_fill((2, 0), |a, b| a == b),
+            [(2, 0), (1, 0), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn labels_open_cells_and_graphs() {
+        let grid = Grid::parse("..#\n###\n#..", |c| c == '.').unwrap();
+        let open = components(grid.find_all(&true), &grid);
+        assert_eq!(
+            open.iter().collect::<Vec<_>>(),
+            [&[(0, 0), (0, 1)], &[(2, 1), (2, 2)]]
+        );
+        assert_eq!(open.id(&(1, 1)), None);
+
+        let mut graph = crate::graph::Graph::new();
+        graph.add_edge_creating(&1, &2, 1);
+        graph.add_edge_creating(&3, &2, 1);
+        graph.add_point(4);
+        let parts = graph.components();
+        assert_eq!(parts.len(), 2);
+        assert!(parts.connected(&1, &3));
+        assert_eq!(parts.size(parts.id(&4).unwrap()), 1);
+    }
+}
@@ -1,70 +1,466 @@
-use std::collections::{btree_map::Keys, BTreeMap, HashMap};
+use std::cmp::Reverse;
+use std::collections::{btree_map, hash_map, BTreeMap, HashMap};
 use std::hash::Hash;
+use std::ops::{Add, AddAssign, BitAnd, BitOr, Index, RangeBounds, Sub, SubAssign};
 
-#[derive(Debug)]
-pub struct BTreeCounter<K: Ord + Clone> {
-    inner: BTreeMap<K, usize>,
+/// The map behind a `Counter`: `BTreeMap` for keys kept in order,
+/// `HashMap` otherwise.
+pub trait CountMap: Default {
+    type Key;
+    type Iter<'a>: Iterator<Item = (&'a Self::Key, &'a usize)>
+    where
+        Self: 'a;
+    type IntoIter: Iterator<Item = (Self::Key, usize)>;
+
+    fn get(&self, k: &Self::Key) -> Option<&usize>;
+    fn get_mut(&mut self, k: &Self::Key) -> Option<&mut usize>;
+    fn insert(&mut self, k: Self::Key, n: usize) -> Option<usize>;
+    fn remove(&mut self, k: &Self::Key) -> Option<usize>;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    fn iter(&self) -> Self::Iter<'_>;
+    fn into_iter(self) -> Self::IntoIter;
 }
 
-impl<K: Ord + Clone> BTreeCounter<K> {
+impl<K: Ord> CountMap for BTreeMap<K, usize> {
+    type Key = K;
+    type Iter<'a>
+        = btree_map::Iter<'a, K, usize>
+    where
+        K: 'a;
+    type IntoIter = btree_map::IntoIter<K, usize>;
+
+    fn get(&self, k: &K) -> Option<&usize> {
+        BTreeMap::get(self, k)
+    }
+
+    fn get_mut(&mut self, k: &K) -> Option<&mut usize> {
+        BTreeMap::get_mut(self, k)
+    }
+
+    fn insert(&mut self, k: K, n: usize) -> Option<usize> {
+        BTreeMap::insert(self, k, n)
+    }
+
+    fn remove(&mut self, k: &K) -> Option<usize> {
+        BTreeMap::remove(self, k)
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BTreeMap::iter(self)
+    }
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self)
+    }
+}
+
+impl<K: Hash + Eq> CountMap for HashMap<K, usize> {
+    type Key = K;
+    type Iter<'a>
+        = hash_map::Iter<'a, K, usize>
+    where
+        K: 'a;
+    type IntoIter = hash_map::IntoIter<K, usize>;
+
+    fn get(&self, k: &K) -> Option<&usize> {
+        HashMap::get(self, k)
+    }
+
+    fn get_mut(&mut self, k: &K) -> Option<&mut usize> {
+        HashMap::get_mut(self, k)
+    }
+
+    fn insert(&mut self, k: K, n: usize) -> Option<usize> {
+        HashMap::insert(self, k, n)
+    }
+
+    fn remove(&mut self, k: &K) -> Option<usize> {
+        HashMap::remove(self, k)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        HashMap::iter(self)
+    }
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self)
+    }
+}
+
+/// A multiset: how many times each key has been added. Keys whose count
+/// drops to zero are removed, so every stored count is at least 1.
+///
+/// The arithmetic works like Python's `Counter`: `+` adds counts, `-`
+/// subtracts them (stopping at zero), `&` keeps the smaller count of keys in
+/// both and `|` the larger count of keys in either.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Counter<M: CountMap> {
+    inner: M,
+}
+
+/// A `Counter` with its keys in order, which adds `total_in`.
+pub type BTreeCounter<K> = Counter<BTreeMap<K, usize>>;
+pub type HashMapCounter<K> = Counter<HashMap<K, usize>>;
+
+impl<M: CountMap> Counter<M> {
     pub fn new() -> Self {
         Self {
-            inner: BTreeMap::new(),
+            inner: M::default(),
         }
     }
 
-    pub fn add_n(&mut self, k: K, n: usize) {
-        let start = self.inner.get(&k).unwrap_or(&0);
-        self.inner.insert(k, start + n);
+    pub fn add_n(&mut self, k: M::Key, n: usize) {
+        if n == 0 {
+            return;
+        }
+        match self.inner.get_mut(&k) {
+            Some(count) => *count += n,
+            None => {
+                self.inner.insert(k, n);
+            }
+        }
     }
 
-    pub fn add(&mut self, k: K) {
+    pub fn add(&mut self, k: M::Key) {
         self.add_n(k, 1)
     }
 
-    pub fn get(&self, k: &K) -> Option<usize> {
+    /// Takes up to `n` off `k`'s count, removing it at zero. Returns the new
+    /// count.
+    pub fn subtract_n(&mut self, k: &M::Key, n: usize) -> usize {
+        let Some(count) = self.inner.get_mut(k) else {
+            return 0;
+        };
+        *count = count.saturating_sub(n);
+        let left = *count;
+        if left == 0 {
+            self.inner.remove(k);
+        }
+        left
+    }
+
+    pub fn subtract(&mut self, k: &M::Key) -> usize {
+        self.subtract_n(k, 1)
+    }
+
+    /// Drops `k` altogether, returning the count it had.
+    pub fn remove(&mut self, k: &M::Key) -> Option<usize> {
+        self.inner.remove(k)
+    }
+
+    /// `None` for keys that were never added; see also indexing, which gives 0.
+    pub fn get(&self, k: &M::Key) -> Option<usize> {
         self.inner.get(k).copied()
     }
 
-    pub fn keys(&self) -> Keys<'_, K, usize> {
-        self.inner.keys()
+    /// The number of distinct keys.
+    pub fn len(&self) -> usize {
+        self.inner.len()
     }
 
-    pub fn iter(&self) -> std::collections::btree_map::Iter<'_, K, usize> {
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// The sum of every count.
+    pub fn total(&self) -> usize {
+        self.inner.iter().map(|(_, count)| count).sum()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &M::Key> {
+        self.inner.iter().map(|(k, _)| k)
+    }
+
+    pub fn iter(&self) -> M::Iter<'_> {
         self.inner.iter()
     }
+
+    /// The `n` keys with the highest counts, highest first. Ties come in the
+    /// map's order, so key order for a `BTreeCounter`.
+    pub fn most_common(&self, n: usize) -> Vec<(&M::Key, usize)> {
+        let mut counts: Vec<(&M::Key, usize)> = self.iter().map(|(k, c)| (k, *c)).collect();
+        counts.sort_by_key(|(_, count)| Reverse(*count));
+        counts.truncate(n);
+        counts
+    }
+
+    /// Every key in either counter, its count worked out from both of its
+    /// counts by `count`.
+    fn combine(self, other: Self, mut count: impl FnMut(usize, usize) -> usize) -> Self
+    where
+        M::Key: Clone,
+    {
+        let mut out = Self::new();
+        for (k, n) in self.inner.iter() {
+            out.add_n(k.clone(), count(*n, other[k]));
+        }
+        for (k, n) in other.inner.into_iter() {
+            if self.inner.get(&k).is_none() {
+                out.add_n(k, count(0, n));
+            }
+        }
+        out
+    }
 }
 
-#[derive(Debug)]
-pub struct HashMapCounter<K: Hash + Clone + Eq> {
-    inner: HashMap<K, usize>,
+impl<K: Ord> Counter<BTreeMap<K, usize>> {
+    /// The total count of keys in `range`: `total_in(100..)` for everything
+    /// from 100 up.
+    pub fn total_in(&self, range: impl RangeBounds<K>) -> usize {
+        self.inner.range(range).map(|(_, count)| count).sum()
+    }
 }
 
-impl<K: Clone + Hash + Eq> HashMapCounter<K> {
-    pub fn new() -> Self {
-        Self {
-            inner: HashMap::new(),
+/// Missing keys count 0.
+impl<M: CountMap> Index<&M::Key> for Counter<M> {
+    type Output = usize;
+
+    fn index(&self, k: &M::Key) -> &usize {
+        self.inner.get(k).unwrap_or(&0)
+    }
+}
+
+impl<M: CountMap> FromIterator<M::Key> for Counter<M> {
+    fn from_iter<I: IntoIterator<Item = M::Key>>(iter: I) -> Self {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+
+impl<M: CountMap> Extend<M::Key> for Counter<M> {
+    fn extend<I: IntoIterator<Item = M::Key>>(&mut self, iter: I) {
+        for k in iter {
+            self.add(k);
         }
     }
+}
 
-    pub fn add_n(&mut self, k: K, n: usize) {
-        let start = self.inner.get(&k).unwrap_or(&0);
-        self.inner.insert(k, start + n);
+impl<M: CountMap> IntoIterator for Counter<M> {
+    type Item = (M::Key, usize);
+    type IntoIter = M::IntoIter;
+
+    fn into_iter(self) -> M::IntoIter {
+        self.inner.into_iter()
     }
+}
 
-    pub fn add(&mut self, k: K) {
-        self.add_n(k, 1)
+impl<'a, M: CountMap> IntoIterator for &'a Counter<M> {
+    type Item = (&'a M::Key, &'a usize);
+    type IntoIter = M::Iter<'a>;
+
+    fn into_iter(self) -> M::Iter<'a> {
+        self.inner.iter()
     }
+}
 
-    pub fn get(&self, k: &K) -> Option<usize> {
-        self.inner.get(k).copied()
+impl<M: CountMap> AddAssign for Counter<M> {
+    fn add_assign(&mut self, other: Self) {
+        for (k, n) in other {
+            self.add_n(k, n);
+        }
     }
+}
 
-    pub fn keys(&self) -> std::collections::hash_map::Keys<'_, K, usize> {
-        self.inner.keys()
+impl<M: CountMap> SubAssign for Counter<M> {
+    fn sub_assign(&mut self, other: Self) {
+        for (k, n) in other {
+            self.subtract_n(&k, n);
+        }
     }
+}
 
-    pub fn iter(&self) -> std::collections::hash_map::Iter<'_, K, usize> {
-        self.inner.iter()
+impl<M: CountMap> Add for Counter<M> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<M: CountMap> Sub for Counter<M> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+impl<M: CountMap> BitAnd for Counter<M>
+where
+    M::Key: Clone,
+{
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.combine(other, usize::min)
+    }
+}
+
+impl<M: CountMap> BitOr for Counter<M>
+where
+    M::Key: Clone,
+{
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.combine(other, usize::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn counts_and_uncounts() {
+        let mut counter: HashMapCounter<char> = "abracadabra".chars().collect();
+        assert_eq!((counter[&'a'], counter[&'z']), (5, 0));
+        assert_eq!((counter.len(), counter.total()), (5, 11));
+        assert_eq!(counter.subtract_n(&'b', 1), 1);
+        assert_eq!(counter.subtract_n(&'b', 5), 0);
+        assert_eq!(counter.get(&'b'), None);
+        assert_eq!(counter.remove(&'r'), Some(2));
+        counter.extend("zz".chars());
+        assert_eq!(counter.get(&'z'), Some(2));
+        assert_eq!(counter.most_common(1), [(&'a', 5)]);
+    }
+
+    #[test]
+    fn ordered_counters_sum_ranges() {
+        let counter: BTreeCounter<i32> = [1, 5, 5, 10, 64, 100, 100].into_iter().collect();
+        assert_eq!(counter.total_in(5..), 6);
+        assert_eq!(counter.total_in(..=5), 3);
+        assert_eq!(counter.total_in(11..100), 1);
+        assert_eq!(counter.most_common(2), [(&5, 2), (&100, 2)]);
+        let keys: Vec<i32> = counter.keys().copied().collect();
+        assert_eq!(keys, [1, 5, 10, 64, 100]);
+    }
+
+    #[test]
+    fn arithmetic_matches_multisets() {
+        let a: BTreeCounter<char> = "aaab".chars().collect();
+        let b: BTreeCounter<char> = "abbc".chars().collect();
+        let pairs = |c: BTreeCounter<char>| c.into_iter().collect::<Vec<_>>();
+        assert_eq!(pairs(a.clone() + b.clone()), [('a', 4), ('b', 3), ('c', 1)]);
+        assert_eq!(pairs(a.clone() - b.clone()), [('a', 2)]);
+        assert_eq!(pairs(b.clone() - a.clone()), [('b', 1), ('c', 1)]);
+        assert_eq!(pairs(a.clone() & b.clone()), [('a', 1), ('b', 1)]);
+        assert_eq!(pairs(a | b), [('a', 3), ('b', 2), ('c', 1)]);
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Add(u8, usize),
+        Subtract(u8, usize),
+        Remove(u8),
+    }
+
+    fn ops() -> impl Strategy<Value = Vec<Op>> {
+        let op = prop_oneof![
+            (0u8..10, 0usize..5).prop_map(|(k, n)| Op::Add(k, n)),
+            (0u8..10, 0usize..5).prop_map(|(k, n)| Op::Subtract(k, n)),
+            (0u8..10).prop_map(Op::Remove),
+        ];
+        prop::collection::vec(op, 0..40)
+    }
+
+    /// The same ops on a plain map, dropping keys that reach zero.
+    fn reference(ops: &[Op]) -> HashMap<u8, usize> {
+        let mut counts: HashMap<u8, usize> = HashMap::new();
+        for op in ops {
+            match *op {
+                Op::Add(k, n) => *counts.entry(k).or_default() += n,
+                Op::Subtract(k, n) => {
+                    if let Some(count) = counts.get_mut(&k) {
+                        *count = count.saturating_sub(n);
+                    }
+                }
+                Op::Remove(k) => {
+                    counts.remove(&k);
+                }
+            }
+            counts.retain(|_, count| *count > 0);
+        }
+        counts
+    }
+
+    fn apply<M: CountMap<Key = u8>>(ops: &[Op]) -> Counter<M> {
+        let mut counter = Counter::new();
+        for op in ops {
+            match *op {
+                Op::Add(k, n) => counter.add_n(k, n),
+                Op::Subtract(k, n) => {
+                    counter.subtract_n(&k, n);
+                }
+                Op::Remove(k) => {
+                    counter.remove(&k);
+                }
+            }
+        }
+        counter
+    }
+
+    fn assert_matches<M: CountMap<Key = u8>>(
+        counter: &Counter<M>,
+        expected: &HashMap<u8, usize>,
+    ) -> Result<(), TestCaseError> {
+        for k in 0u8..10 {
+            prop_assert_eq!(counter.get(&k), expected.get(&k).copied());
+            prop_assert_eq!(counter[&k], expected.get(&k).copied().unwrap_or(0));
+        }
+        prop_assert_eq!(counter.len(), expected.len());
+        prop_assert_eq!(counter.total(), expected.values().sum::<usize>());
+        let top: Vec<usize> = counter.most_common(3).iter().map(|(_, n)| *n).collect();
+        let mut want: Vec<usize> = expected.values().copied().collect();
+        want.sort_by_key(|n| Reverse(*n));
+        want.truncate(3);
+        prop_assert_eq!(top, want);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn counters_match_a_plain_map(ops in ops()) {
+            let expected = reference(&ops);
+            assert_matches(&apply::<HashMap<u8, usize>>(&ops), &expected)?;
+            let ordered: BTreeCounter<u8> = apply(&ops);
+            assert_matches(&ordered, &expected)?;
+            for (lo, hi) in [(0, 10), (3, 7), (5, 5)] {
+                let want: usize = expected.iter().filter(|(k, _)| (lo..hi).contains(*k)).map(|(_, n)| n).sum();
+                prop_assert_eq!(ordered.total_in(lo..hi), want);
+            }
+        }
+
+        #[test]
+        fn arithmetic_works_key_by_key(a in ops(), b in ops()) {
+            let (a_counts, b_counts) = (reference(&a), reference(&b));
+            let (a, b): (BTreeCounter<u8>, BTreeCounter<u8>) = (apply(&a), apply(&b));
+            let by_key = |count: fn(usize, usize) -> usize| -> HashMap<u8, usize> {
+                (0u8..10)
+                    .map(|k| (k, count(a_counts.get(&k).copied().unwrap_or(0), b_counts.get(&k).copied().unwrap_or(0))))
+                    .filter(|(_, n)| *n > 0)
+                    .collect()
+            };
+            assert_matches(&(a.clone() + b.clone()), &by_key(|x, y| x + y))?;
+            assert_matches(&(a.clone() - b.clone()), &by_key(usize::saturating_sub))?;
+            assert_matches(&(a.clone() & b.clone()), &by_key(usize::min))?;
+            assert_matches(&(a | b), &by_key(usize::max))?;
+        }
     }
 }
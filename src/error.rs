@@ -0,0 +1,229 @@
+use core::fmt;
+use std::any::Any;
+use std::error::Error;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum AocHelperError {
+    FileReadError {
+        path: PathBuf,
+        source: io::Error,
+    },
+    FileWriteError {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// `line` and `column` are 1-based, like an editor shows them.
+    ParseError {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    UnknownCustomTest {
+        fname: String,
+    },
+    /// The day has no `test_input` to fall back on.
+    NoTestInput {
+        day: u32,
+    },
+    /// Downloading an input failed, see `provider::HttpFetcher`.
+    FetchError {
+        day: u32,
+        message: String,
+    },
+    /// Days run from 1 to 25.
+    InvalidDay {
+        day: u32,
+    },
+    /// None of the places inputs are looked for exist.
+    NoInputDir {
+        tried: Vec<PathBuf>,
+    },
+    /// A setting from the environment or `.env` that can't be used.
+    ConfigError {
+        key: String,
+        message: String,
+    },
+    /// `elapsed` is how long the solution ran before it was given up on.
+    TimeoutError {
+        elapsed: Duration,
+    },
+    /// `new-day` won't touch a day that already has a crate or registration.
+    DayExists {
+        day: u32,
+        path: PathBuf,
+    },
+    /// `new-day` couldn't work out where to register the day in `path`.
+    ScaffoldError {
+        path: PathBuf,
+        message: String,
+    },
+    /// A solution panicked; `message` is the panic's, when it had one.
+    SolutionPanicked {
+        message: String,
+    },
+}
+
+pub type AocResult<T> = std::result::Result<T, AocHelperError>;
+
+impl AocHelperError {
+    pub fn parse_error(line: usize, column: usize, message: impl fmt::Display) -> Self {
+        AocHelperError::ParseError {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn config_error(key: &str, message: impl fmt::Display) -> Self {
+        AocHelperError::ConfigError {
+            key: key.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Turns a payload from `catch_unwind` into `SolutionPanicked`.
+    pub fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "no message".to_string(),
+            },
+        };
+        AocHelperError::SolutionPanicked { message }
+    }
+
+    /// Parse error pointing at `token`, a slice of `line`. `line_idx` is the
+    /// 0-based index from `lines().enumerate()`.
+    pub fn parse_error_at(
+        line_idx: usize,
+        line: &str,
+        token: &str,
+        message: impl fmt::Display,
+    ) -> Self {
+        AocHelperError::parse_error(line_idx + 1, column_of(line, token) + 1, message)
+    }
+
+    /// Parse error pointing at `token`, a slice of the whole `input`.
+    pub fn parse_error_in(input: &str, token: &str, message: impl fmt::Display) -> Self {
+        let offset = column_of(input, token);
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        AocHelperError::parse_error(
+            before.matches('\n').count() + 1,
+            offset - line_start + 1,
+            message,
+        )
+    }
+}
+
+/// Byte offset of `token` inside `line`, or 0 if it isn't a slice of it.
+fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    if offset <= line.len() {
+        offset
+    } else {
+        0
+    }
+}
+
+/// Parses `token` (a slice of `line`) with `FromStr`, reporting where it sits
+/// in the input on failure.
+pub fn parse_token<T>(line_idx: usize, line: &str, token: &str) -> AocResult<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    token.trim().parse::<T>().map_err(|e| {
+        AocHelperError::parse_error_at(line_idx, line, token, format!("{:?}: {}", token, e))
+    })
+}
+
+/// Like `parse_token`, for a `token` sliced out of the whole input (regex
+/// captures and the like) rather than out of a single line.
+pub fn parse_token_in<T>(input: &str, token: &str) -> AocResult<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    token
+        .trim()
+        .parse::<T>()
+        .map_err(|e| AocHelperError::parse_error_in(input, token, format!("{:?}: {}", token, e)))
+}
+
+impl fmt::Display for AocHelperError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocHelperError::FileReadError { path, source } => {
+                write!(f, "Could not read {}: {}", path.display(), source)
+            }
+            AocHelperError::FileWriteError { path, source } => {
+                write!(f, "Could not write {}: {}", path.display(), source)
+            }
+            AocHelperError::ParseError {
+                line,
+                column,
+                message,
+            } => {
+                write!(
+                    f,
+                    "Parse error at line {}, column {}: {}",
+                    line, column, message
+                )
+            }
+            AocHelperError::UnknownCustomTest { fname } => {
+                write!(f, "Unknown custom test file {}", fname)
+            }
+            AocHelperError::NoTestInput { day } => {
+                write!(f, "Day {} has no test input", day)
+            }
+            AocHelperError::FetchError { day, message } => {
+                write!(f, "Could not fetch day {} input: {}", day, message)
+            }
+            AocHelperError::InvalidDay { day } => {
+                write!(f, "There is no day {}, days run from 1 to 25", day)
+            }
+            AocHelperError::NoInputDir { tried } => {
+                write!(f, "No inputs directory found, tried")?;
+                for (idx, path) in tried.iter().enumerate() {
+                    let sep = if idx == 0 { " " } else { ", " };
+                    write!(f, "{}{}", sep, path.display())?;
+                }
+                write!(
+                    f,
+                    "; set STATIC_BASE_PATH or create static/ in the workspace root"
+                )
+            }
+            AocHelperError::ConfigError { key, message } => {
+                write!(f, "Bad {}: {}", key, message)
+            }
+            AocHelperError::TimeoutError { elapsed } => {
+                write!(f, "Timed out after {:.2?}", elapsed)
+            }
+            AocHelperError::DayExists { day, path } => {
+                write!(f, "Day {} already exists in {}", day, path.display())
+            }
+            AocHelperError::ScaffoldError { path, message } => {
+                write!(f, "Could not update {}: {}", path.display(), message)
+            }
+            AocHelperError::SolutionPanicked { message } => {
+                write!(f, "Solution panicked: {}", message)
+            }
+        }
+    }
+}
+
+impl Error for AocHelperError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocHelperError::FileReadError { source, .. }
+            | AocHelperError::FileWriteError { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use core::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Integer types usable as `Point` coordinates.
+pub trait Coord: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn rem_euclid(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {
+        $(
+            impl Coord for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn rem_euclid(self, rhs: Self) -> Self {
+                    <$t>::rem_euclid(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_coord!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// A `(row, col)` position or offset, the same way round as `grid::Pos`.
+/// `Point` on its own is `Point<i32>`, what most days used as a tuple.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Point<T = i32> {
+    pub row: T,
+    pub col: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(row: T, col: T) -> Self {
+        Self { row, col }
+    }
+}
+
+impl<T: Coord> Point<T> {
+    pub const ORIGIN: Self = Self::new(T::ZERO, T::ZERO);
+
+    pub fn manhattan(&self, other: &Self) -> T {
+        let abs_diff = |a: T, b: T| if a > b { a - b } else { b - a };
+        abs_diff(self.row, other.row) + abs_diff(self.col, other.col)
+    }
+
+    /// Wraps each coordinate into `0..bounds`, for maps that loop around at
+    /// the edges. Negative coordinates come out positive.
+    pub fn rem_euclid(&self, bounds: &Self) -> Self {
+        Self::new(
+            self.row.rem_euclid(bounds.row),
+            self.col.rem_euclid(bounds.col),
+        )
+    }
+
+    /// The four points up, right, down and left of this one.
+    pub fn neighbors4(&self) -> [Self; 4]
+    where
+        T: Neg<Output = T>,
+    {
+        Direction::CARDINAL.map(|dir| *self + dir)
+    }
+
+    /// `neighbors4` plus the diagonals, clockwise from up.
+    pub fn neighbors8(&self) -> [Self; 8]
+    where
+        T: Neg<Output = T>,
+    {
+        Direction::ALL.map(|dir| *self + dir)
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((row, col): (T, T)) -> Self {
+        Self::new(row, col)
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(p: Point<T>) -> Self {
+        (p.row, p.col)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.row - rhs.row, self.col - rhs.col)
+    }
+}
+
+/// Scales both coordinates.
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        Self::new(self.row * rhs, self.col * rhs)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.row, -self.col)
+    }
+}
+
+impl<T: Add<Output = T> + Copy> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Sub<Output = T> + Copy> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/// One step in `dir`.
+impl<T: Coord + Neg<Output = T>> Add<Direction> for Point<T> {
+    type Output = Self;
+
+    fn add(self, dir: Direction) -> Self {
+        self + dir.delta()
+    }
+}
+
+impl<T: Coord + Neg<Output = T>> AddAssign<Direction> for Point<T> {
+    fn add_assign(&mut self, dir: Direction) {
+        *self = *self + dir;
+    }
+}
+
+/// Screen directions: `Up` is towards row 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// Clockwise from `Up`.
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Clockwise from `Up`, diagonals included.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// The `^`, `v`, `<` and `>` arrows puzzles use for moves and facings.
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::Up),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            '>' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    /// The arrow for a cardinal direction, `None` for a diagonal.
+    pub fn to_char(self) -> Option<char> {
+        match self {
+            Direction::Up => Some('^'),
+            Direction::Down => Some('v'),
+            Direction::Left => Some('<'),
+            Direction::Right => Some('>'),
+            _ => None,
+        }
+    }
+
+    pub fn delta<T: Coord + Neg<Output = T>>(self) -> Point<T> {
+        let (one, zero) = (T::ONE, T::ZERO);
+        let (row, col) = match self {
+            Direction::Up => (-one, zero),
+            Direction::UpRight => (-one, one),
+            Direction::Right => (zero, one),
+            Direction::DownRight => (one, one),
+            Direction::Down => (one, zero),
+            Direction::DownLeft => (one, -one),
+            Direction::Left => (zero, -one),
+            Direction::UpLeft => (-one, -one),
+        };
+        Point::new(row, col)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        (self as usize) % 2 == 1
+    }
+
+    /// A quarter turn clockwise.
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// A quarter turn anticlockwise.
+    pub fn turn_left(self) -> Direction {
+        self.rotate(6)
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.rotate(4)
+    }
+
+    /// `eighths` eighth-turns clockwise.
+    fn rotate(self, eighths: usize) -> Direction {
+        Direction::ALL[(self as usize + eighths) % 8]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Point::new(1, 2);
+        let b = Point::new(3, -4);
+        assert_eq!(a + b, Point::new(4, -2));
+        assert_eq!(a - b, Point::new(-2, 6));
+        assert_eq!(b * 3, Point::new(9, -12));
+        assert_eq!(-b, Point::new(-3, 4));
+
+        let mut c = a;
+        c += b;
+        assert_eq!(c, Point::new(4, -2));
+        c -= b;
+        assert_eq!(c, a);
+    }
+
+    #[test]
+    fn origin_is_the_default() {
+        assert_eq!(Point::<i32>::ORIGIN, Point::default());
+        assert_eq!(Point::<usize>::ORIGIN, Point::new(0, 0));
+    }
+
+    #[test]
+    fn orders_row_major() {
+        let mut points = vec![Point::new(1, 0), Point::new(0, 2), Point::new(0, 1)];
+        points.sort();
+        assert_eq!(points, [(0, 1), (0, 2), (1, 0)].map(Point::from));
+    }
+
+    #[test]
+    fn steps_in_a_direction() {
+        let mut p = Point::new(5, 5);
+        assert_eq!(p + Direction::Up, Point::new(4, 5));
+        assert_eq!(p + Direction::Left, Point::new(5, 4));
+        p += Direction::DownRight;
+        assert_eq!(p, Point::new(6, 6));
+    }
+
+    #[test]
+    fn manhattan_is_symmetric() {
+        let a = Point::new(-1, 2);
+        let b = Point::new(3, -4);
+        assert_eq!(a.manhattan(&b), 10);
+        assert_eq!(b.manhattan(&a), 10);
+        assert_eq!(a.manhattan(&a), 0);
+        assert_eq!(Point::<usize>::new(7, 1).manhattan(&Point::new(2, 4)), 8);
+    }
+
+    #[test]
+    fn rem_euclid_wraps_negative_coordinates() {
+        let bounds = Point::new(7, 11);
+        assert_eq!(Point::new(-1, -12).rem_euclid(&bounds), Point::new(6, 10));
+        assert_eq!(Point::new(-7, 0).rem_euclid(&bounds), Point::ORIGIN);
+        assert_eq!(Point::new(15, 23).rem_euclid(&bounds), Point::new(1, 1));
+    }
+
+    #[test]
+    fn tuple_conversions() {
+        let p: Point<u8> = (3, 4).into();
+        assert_eq!(p, Point::new(3, 4));
+        assert_eq!(<(u8, u8)>::from(p), (3, 4));
+        assert_eq!(p.to_string(), "(3, 4)");
+    }
+
+    #[test]
+    fn neighbors_go_clockwise_from_up() {
+        let p = Point::new(0, 0);
+        assert_eq!(
+            p.neighbors4(),
+            [(-1, 0), (0, 1), (1, 0), (0, -1)].map(Point::from)
+        );
+        let all = p.neighbors8();
+        assert_eq!(all.len(), 8);
+        assert_eq!(
+            all,
+            [
+                (-1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+                (1, 0),
+                (1, -1),
+                (0, -1),
+                (-1, -1)
+            ]
+            .map(Point::from)
+        );
+    }
+
+    #[test]
+    fn diagonal_deltas() {
+        assert_eq!(Direction::UpRight.delta::<i32>(), Point::new(-1, 1));
+        assert_eq!(Direction::DownLeft.delta::<i64>(), Point::new(1, -1));
+        let diagonals: Vec<_> = Direction::ALL
+            .into_iter()
+            .filter(|d| d.is_diagonal())
+            .collect();
+        assert_eq!(
+            diagonals,
+            [
+                Direction::UpRight,
+                Direction::DownRight,
+                Direction::DownLeft,
+                Direction::UpLeft
+            ]
+        );
+    }
+
+    #[test]
+    fn cardinal_deltas_are_unit_steps() {
+        for dir in Direction::CARDINAL {
+            assert_eq!(dir.delta::<i32>().manhattan(&Point::ORIGIN), 1);
+        }
+        for dir in Direction::ALL {
+            let d = dir.delta::<i32>();
+            assert_eq!(d.row.abs().max(d.col.abs()), 1);
+        }
+    }
+
+    #[test]
+    fn four_turns_come_back_round() {
+        for dir in Direction::ALL {
+            let mut right = dir;
+            let mut left = dir;
+            for _ in 0..4 {
+                right = right.turn_right();
+                left = left.turn_left();
+            }
+            assert_eq!((right, left), (dir, dir));
+        }
+    }
+
+    #[test]
+    fn turns_undo_each_other() {
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(dir.opposite().opposite(), dir);
+            assert_eq!(dir.opposite(), dir.turn_right().turn_right());
+            assert_eq!(dir.opposite().delta::<i32>(), -dir.delta::<i32>());
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::UpLeft.turn_right(), Direction::UpRight);
+    }
+
+    #[test]
+    fn arrows_round_trip() {
+        for c in ['^', 'v', '<', '>'] {
+            let dir = Direction::from_char(c).unwrap();
+            assert!(!dir.is_diagonal());
+            assert_eq!(dir.to_char(), Some(c));
+        }
+        for dir in Direction::CARDINAL {
+            assert_eq!(Direction::from_char(dir.to_char().unwrap()), Some(dir));
+        }
+        assert_eq!(Direction::from_char('x'), None);
+        assert_eq!(Direction::DownRight.to_char(), None);
+    }
+}
@@ -1,14 +1,58 @@
 use std::{
+    cmp::Reverse,
     collections::{BinaryHeap, HashMap, HashSet},
     hash::Hash,
 };
 
 use tracing::{debug, error};
 
+use crate::components::{self, Components};
+use crate::search::{self, BfsResult};
+
+/// Anything that can list the nodes one step on from a node, so the searches
+/// here and in `search` can run on a stored `Graph`, a `Grid<bool>` or a
+/// closure without building edges first.
+///
+/// Closures need their argument type written out, `|p: &Pos| ...`, for Rust
+/// to accept them for any borrow of the node.
+pub trait Neighbors<T> {
+    fn neighbors(&self, node: &T) -> impl Iterator<Item = T>;
+}
+
+/// `Neighbors` with the cost of each step.
+pub trait WeightedNeighbors<T> {
+    fn weighted_neighbors(&self, node: &T) -> impl Iterator<Item = (T, i32)>;
+}
+
+impl<T, I, F> Neighbors<T> for F
+where
+    F: Fn(&T) -> I,
+    I: IntoIterator<Item = T>,
+{
+    fn neighbors(&self, node: &T) -> impl Iterator<Item = T> {
+        self(node).into_iter()
+    }
+}
+
+impl<T, I, F> WeightedNeighbors<T> for F
+where
+    F: Fn(&T) -> I,
+    I: IntoIterator<Item = (T, i32)>,
+{
+    fn weighted_neighbors(&self, node: &T) -> impl Iterator<Item = (T, i32)> {
+        self(node).into_iter()
+    }
+}
+
+/// A directed graph with `i32` edge costs. Undirected graphs store each edge
+/// both ways, see `add_undirected_edge`.
 #[derive(Debug)]
 pub struct Graph<T: Hash + Eq + Copy + Clone + core::fmt::Debug + Ord> {
     pub points: HashSet<T>,
     edges: HashMap<T, HashMap<T, i32>>,
+    // the other end of every edge into each node, so removals and in-degrees
+    // don't have to scan every edge
+    incoming: HashMap<T, HashSet<T>>,
 }
 
 pub const SCORE_MAX: i32 = 1_234_567_890;
@@ -17,6 +61,7 @@ impl<T: Hash + Eq + Copy + Clone + core::fmt::Debug + Ord> Graph<T> {
         Graph {
             points: HashSet::new(),
             edges: HashMap::new(),
+            incoming: HashMap::new(),
         }
     }
 
@@ -24,6 +69,8 @@ impl<T: Hash + Eq + Copy + Clone + core::fmt::Debug + Ord> Graph<T> {
         self.points.insert(dp);
     }
 
+    /// Adds or re-costs the edge from `dp_start` to `dp_end`. Both points must
+    /// already be in the graph, returns false (and adds nothing) otherwise.
     pub fn add_edge(&mut self, dp_start: &T, dp_end: &T, cost: i32) -> bool {
         debug!(
             "Inserting edge between {:?} and {:?} cost: {cost}",
@@ -33,15 +80,84 @@ impl<T: Hash + Eq + Copy + Clone + core::fmt::Debug + Ord> Graph<T> {
             error!("Couldn't insert edge b/c one point does not exist");
             return false;
         }
+        self.insert_edge(dp_start, dp_end, cost);
+        true
+    }
+
+    /// Like `add_edge`, but adds either point if it isn't there yet. Returns
+    /// the edge's previous cost, if it already existed.
+    pub fn add_edge_creating(&mut self, dp_start: &T, dp_end: &T, cost: i32) -> Option<i32> {
+        self.points.insert(*dp_start);
+        self.points.insert(*dp_end);
+        self.insert_edge(dp_start, dp_end, cost)
+    }
+
+    fn insert_edge(&mut self, dp_start: &T, dp_end: &T, cost: i32) -> Option<i32> {
+        self.incoming.entry(*dp_end).or_default().insert(*dp_start);
+        self.edges
+            .entry(*dp_start)
+            .or_default()
+            .insert(*dp_end, cost)
+    }
+
+    /// `add_edge_creating` in both directions.
+    pub fn add_undirected_edge(&mut self, a: &T, b: &T, cost: i32) {
+        self.add_edge_creating(a, b, cost);
+        self.add_edge_creating(b, a, cost);
+    }
 
-        let working_map: &mut HashMap<T, i32>;
-        if !self.edges.contains_key(dp_start) {
-            self.edges.insert(dp_start.clone(), HashMap::new());
+    /// Removes the edge from `dp_start` to `dp_end`, returning its cost.
+    pub fn remove_edge(&mut self, dp_start: &T, dp_end: &T) -> Option<i32> {
+        let cost = self.edges.get_mut(dp_start)?.remove(dp_end)?;
+        if let Some(froms) = self.incoming.get_mut(dp_end) {
+            froms.remove(dp_start);
         }
-        working_map = self.edges.get_mut(&dp_start).unwrap();
-        working_map.insert(dp_end.clone(), cost);
+        Some(cost)
+    }
 
-        true
+    /// `remove_edge` in both directions.
+    pub fn remove_undirected_edge(&mut self, a: &T, b: &T) {
+        self.remove_edge(a, b);
+        self.remove_edge(b, a);
+    }
+
+    /// Removes `dp` and every edge into or out of it. Returns whether it was
+    /// there.
+    pub fn remove_point(&mut self, dp: &T) -> bool {
+        for next in self.edges.remove(dp).unwrap_or_default().keys() {
+            if let Some(froms) = self.incoming.get_mut(next) {
+                froms.remove(dp);
+            }
+        }
+        for prev in self.incoming.remove(dp).unwrap_or_default() {
+            if let Some(edges) = self.edges.get_mut(&prev) {
+                edges.remove(dp);
+            }
+        }
+        self.points.remove(dp)
+    }
+
+    /// The cost of the edge from `dp_start` to `dp_end`, if there is one.
+    pub fn edge_cost(&self, dp_start: &T, dp_end: &T) -> Option<i32> {
+        self.edges.get(dp_start)?.get(dp_end).copied()
+    }
+
+    /// Every edge as `(start, end, cost)`, in no particular order. An
+    /// undirected edge shows up once each way.
+    pub fn edges(&self) -> impl Iterator<Item = (T, T, i32)> + '_ {
+        self.edges
+            .iter()
+            .flat_map(|(start, ends)| ends.iter().map(|(end, cost)| (*start, *end, *cost)))
+    }
+
+    /// The number of edges out of `dp`; for an undirected graph, its degree.
+    pub fn degree(&self, dp: &T) -> usize {
+        self.edges.get(dp).map_or(0, |edges| edges.len())
+    }
+
+    /// The number of edges into `dp`.
+    pub fn in_degree(&self, dp: &T) -> usize {
+        self.incoming.get(dp).map_or(0, |froms| froms.len())
     }
 
     pub fn get_neighbors(&self, p: &T) -> Option<Vec<(T, i32)>> {
@@ -54,83 +170,748 @@ impl<T: Hash + Eq + Copy + Clone + core::fmt::Debug + Ord> Graph<T> {
         Some(out)
     }
 
-    fn reconstruct_path(start_ref: &T, end: &T, prevs: &HashMap<T, Option<T>>) -> Vec<T> {
-        let mut out: Vec<T> = Vec::new();
-        let mut cur: &T = end;
-        while let Some(Some(prev)) = prevs.get(&cur) {
-            out.push(cur.clone());
-            cur = prev;
+    /// The edges out of `p` with their costs, nothing for a node without any.
+    pub fn successors(&self, p: &T) -> impl Iterator<Item = (T, i32)> + '_ {
+        self.edges
+            .get(p)
+            .into_iter()
+            .flatten()
+            .map(|(n, c)| (*n, *c))
+    }
+
+    /// Breadth-first search along the stored edges, ignoring their costs.
+    pub fn bfs(&self, starts: impl IntoIterator<Item = T>) -> BfsResult<T> {
+        search::bfs(self, starts)
+    }
+
+    /// A* along the stored edges from `start` to `end`, see `search::astar`.
+    pub fn astar(
+        &self,
+        start: &T,
+        end: &T,
+        heuristic: impl FnMut(&T) -> i32,
+    ) -> Option<(i32, Vec<T>)> {
+        search::astar(self, [*start], |p| p == end, heuristic)
+    }
+
+    /// Dijkstra from `start` to `end`. Returns the total cost and one cheapest
+    /// path, `start` and `end` included, or `None` when `end` can't be reached.
+    /// Edge costs must not be negative.
+    pub fn shortest_path_len(&self, start: &T, end: &T) -> Option<(i32, Vec<T>)> {
+        search::dijkstra_to(self, [*start], |p| p == end)
+    }
+
+    /// See `shortest_paths`.
+    pub fn shortest_paths_from(&self, start: &T) -> ShortestPaths<T> {
+        shortest_paths(self, start)
+    }
+
+    /// Connected components with every edge taken as undirected, numbered in
+    /// order of their smallest point.
+    pub fn components(&self) -> Components<T> {
+        let mut points: Vec<T> = self.points.iter().copied().collect();
+        points.sort();
+        components::components(points, self)
+    }
+
+    /// Each point's neighbours, self-loops left out, for the clique searches.
+    fn adjacency(&self) -> HashMap<T, HashSet<T>> {
+        self.points
+            .iter()
+            .map(|p| {
+                let neighbors = self.neighbors(p).filter(|n| n != p).collect();
+                (*p, neighbors)
+            })
+            .collect()
+    }
+
+    /// Every maximal clique (one no other point can join), found with
+    /// Bron–Kerbosch with pivoting. Each clique is sorted, and so is the list.
+    ///
+    /// The clique searches treat the graph as undirected, so every edge needs
+    /// its reverse as well; `add_undirected_edge` takes care of that.
+    pub fn maximal_cliques(&self) -> Vec<Vec<T>> {
+        let adjacency = self.adjacency();
+        let mut cliques = Vec::new();
+        bron_kerbosch(
+            &adjacency,
+            &mut Vec::new(),
+            self.points.clone(),
+            HashSet::new(),
+            &mut cliques,
+        );
+        cliques.sort();
+        cliques
+    }
+
+    /// The largest clique, sorted; the first in sorted order when several tie.
+    /// Empty for an empty graph.
+    pub fn maximum_clique(&self) -> Vec<T> {
+        self.maximal_cliques()
+            .into_iter()
+            .max_by(|a, b| a.len().cmp(&b.len()).then(b.cmp(a)))
+            .unwrap_or_default()
+    }
+
+    /// Every clique of exactly `k` points, each sorted and listed once, the
+    /// list sorted. `k_cliques(3)` is every triangle.
+    pub fn k_cliques(&self, k: usize) -> Vec<Vec<T>> {
+        let adjacency = self.adjacency();
+        let mut cliques = Vec::new();
+        if k == 0 {
+            return cliques;
+        }
+        let mut points: Vec<T> = self.points.iter().copied().collect();
+        points.sort();
+        extend_cliques(&adjacency, k, &mut Vec::new(), &points, &mut cliques);
+        cliques
+    }
+}
+
+/// Reports every maximal clique that contains all of `current`, some of
+/// `candidates` and none of `excluded`.
+fn bron_kerbosch<T: Hash + Eq + Copy + Ord>(
+    adjacency: &HashMap<T, HashSet<T>>,
+    current: &mut Vec<T>,
+    mut candidates: HashSet<T>,
+    mut excluded: HashSet<T>,
+    cliques: &mut Vec<Vec<T>>,
+) {
+    // every maximal clique holds the pivot or one of its non-neighbours, so
+    // branching on those alone still finds them all
+    let Some(pivot) = candidates
+        .union(&excluded)
+        .max_by_key(|p| adjacency[*p].intersection(&candidates).count())
+        .copied()
+    else {
+        let mut clique = current.clone();
+        clique.sort();
+        cliques.push(clique);
+        return;
+    };
+    let branches: Vec<T> = candidates.difference(&adjacency[&pivot]).copied().collect();
+    for point in branches {
+        let neighbors = &adjacency[&point];
+        current.push(point);
+        bron_kerbosch(
+            adjacency,
+            current,
+            candidates.intersection(neighbors).copied().collect(),
+            excluded.intersection(neighbors).copied().collect(),
+            cliques,
+        );
+        current.pop();
+        candidates.remove(&point);
+        excluded.insert(point);
+    }
+}
+
+/// Grows `current` to `k` points using only `candidates`, which are sorted
+/// and all larger than what's in `current`, so each clique is built once.
+fn extend_cliques<T: Hash + Eq + Copy + Ord>(
+    adjacency: &HashMap<T, HashSet<T>>,
+    k: usize,
+    current: &mut Vec<T>,
+    candidates: &[T],
+    cliques: &mut Vec<Vec<T>>,
+) {
+    if current.len() == k {
+        cliques.push(current.clone());
+        return;
+    }
+    for (idx, point) in candidates.iter().enumerate() {
+        let next: Vec<T> = candidates[idx + 1..]
+            .iter()
+            .filter(|c| adjacency[point].contains(c))
+            .copied()
+            .collect();
+        if current.len() + 1 + next.len() < k {
+            continue;
         }
-        out.iter().map(|x| *x).rev().collect()
+        current.push(*point);
+        extend_cliques(adjacency, k, current, &next, cliques);
+        current.pop();
     }
+}
 
-    pub fn shortest_path_len(&self, start_ref: &T, end: &T) -> (i32, Vec<T>) {
-        let mut working_set: HashSet<T>;
-        let mut working_q: BinaryHeap<(T, i32)> = BinaryHeap::new();
-        let mut prev: HashMap<T, Option<T>> = HashMap::new();
-        let mut distances: HashMap<T, i32> = HashMap::new();
-        let start = start_ref.clone();
+impl<T: Hash + Eq + Copy + Clone + core::fmt::Debug + Ord> Neighbors<T> for Graph<T> {
+    fn neighbors(&self, node: &T) -> impl Iterator<Item = T> {
+        self.successors(node).map(|(n, _)| n)
+    }
+}
 
-        working_set = self.points.iter().map(|p| p.clone()).collect();
-        debug!("start: {:?}", start);
+impl<T: Hash + Eq + Copy + Clone + core::fmt::Debug + Ord> WeightedNeighbors<T> for Graph<T> {
+    fn weighted_neighbors(&self, node: &T) -> impl Iterator<Item = (T, i32)> {
+        self.successors(node)
+    }
+}
 
-        // let Some(cur) = working_q.pop() else {return;};
+/// Dijkstra from `start` to everywhere it leads, keeping every predecessor
+/// that gives a node its cheapest cost rather than just the first one found.
+/// Edge costs must not be negative.
+pub fn shortest_paths<T, N>(graph: &N, start: &T) -> ShortestPaths<T>
+where
+    T: Hash + Eq + Copy + Clone + core::fmt::Debug + Ord,
+    N: WeightedNeighbors<T> + ?Sized,
+{
+    let mut distances: HashMap<T, i32> = HashMap::new();
+    let mut prevs: HashMap<T, Vec<T>> = HashMap::new();
+    let mut queue: BinaryHeap<Reverse<(i32, T)>> = BinaryHeap::new();
 
-        for p in working_set.iter() {
-            if *p == start {
-                debug!("Skipping {:?}", p);
-                continue;
+    distances.insert(*start, 0);
+    queue.push(Reverse((0, *start)));
+    while let Some(Reverse((cost, cur))) = queue.pop() {
+        if distances.get(&cur).is_some_and(|best| cost > *best) {
+            continue;
+        }
+        for (next, edge_cost) in graph.weighted_neighbors(&cur) {
+            let alt = cost + edge_cost;
+            match distances.get(&next) {
+                Some(best) if alt > *best => {}
+                Some(best) if alt == *best => {
+                    if next != *start {
+                        prevs.entry(next).or_default().push(cur);
+                    }
+                }
+                _ => {
+                    distances.insert(next, alt);
+                    prevs.insert(next, vec![cur]);
+                    queue.push(Reverse((alt, next)));
+                }
             }
-            distances.insert(p.clone(), SCORE_MAX);
-            prev.insert(p.clone(), None);
-            working_q.push((p.clone(), SCORE_MAX));
-        }
-        debug!("Distances: {:?}", distances);
-        distances.insert(start.clone(), 0);
-        let dbstart = distances.get(&start);
-        debug!("{:?}", dbstart);
-        prev.insert(start.clone(), None);
-        working_q.push((start.clone(), 0));
-
-        debug!("Distances: {:?}", distances);
-        while let Some(cur) = working_q.pop() {
-            // debug!("Working queue state: {:?}", working_q);
-            working_set.remove(&cur.0);
-            debug!("Starting from {:?}", cur);
-            let Some(neighbors) = self.get_neighbors(&cur.0) else {
-                debug!("No neighbors for {:?} ??", cur);
+        }
+    }
+
+    debug!("Reached {} nodes from {:?}", distances.len(), start);
+    ShortestPaths {
+        start: *start,
+        distances,
+        prevs,
+    }
+}
+
+/// Everything one run of Dijkstra learns from a single start, see
+/// `shortest_paths`.
+///
+/// The predecessors form a DAG: following them back from any reached node
+/// always ends at `start`, and every way of doing so is a cheapest path.
+/// With zero-cost cycles in the graph that no longer holds and
+/// `paths_to` may not finish.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<T: Hash + Eq + Copy + Clone + core::fmt::Debug + Ord> {
+    start: T,
+    distances: HashMap<T, i32>,
+    prevs: HashMap<T, Vec<T>>,
+}
+
+impl<T: Hash + Eq + Copy + Clone + core::fmt::Debug + Ord> ShortestPaths<T> {
+    pub fn start(&self) -> &T {
+        &self.start
+    }
+
+    /// `None` when `node` can't be reached.
+    pub fn distance(&self, node: &T) -> Option<i32> {
+        self.distances.get(node).copied()
+    }
+
+    /// Every reachable node with its cost.
+    pub fn distances(&self) -> &HashMap<T, i32> {
+        &self.distances
+    }
+
+    /// The nodes one step before `node` on its cheapest paths. Empty for the
+    /// start and for nodes that weren't reached.
+    pub fn predecessors(&self, node: &T) -> &[T] {
+        self.prevs.get(node).map(|p| p.as_slice()).unwrap_or(&[])
+    }
+
+    /// Of `targets`, the reachable ones with the lowest cost, and that cost.
+    /// Handy when the goal is a tile that can be entered facing several ways.
+    pub fn best_targets(&self, targets: &[T]) -> Option<(i32, Vec<T>)> {
+        let best = targets.iter().filter_map(|t| self.distance(t)).min()?;
+        let reached = targets
+            .iter()
+            .filter(|t| self.distance(t) == Some(best))
+            .copied()
+            .collect();
+        Some((best, reached))
+    }
+
+    /// Every cheapest path from the start to `target`, both ends included.
+    /// Empty when `target` can't be reached. The count can grow
+    /// exponentially; `nodes_on_paths_to` is the cheap way to ask which
+    /// nodes any of them use.
+    pub fn paths_to(&self, target: &T) -> Vec<Vec<T>> {
+        if !self.distances.contains_key(target) {
+            return Vec::new();
+        }
+        let mut paths = Vec::new();
+        // partial paths, built backwards from the target
+        let mut stack: Vec<Vec<T>> = vec![vec![*target]];
+        while let Some(partial) = stack.pop() {
+            let last = partial[partial.len() - 1];
+            if last == self.start {
+                paths.push(partial.into_iter().rev().collect());
                 continue;
-            };
-            debug!("Neighbors for {:?} => {:?}", cur, neighbors);
-            for n in neighbors {
-                debug!("\t Working on {:?}", n);
-                let alt_dist_to_n = cur.1 + n.1; // is it cheaper to get to n via cur?
-                let n_prev_score = distances.get(&n.0).unwrap();
-                if alt_dist_to_n < *n_prev_score {
-                    debug!("New score for neighbor {:?}", n);
-                    distances.insert(n.0, alt_dist_to_n);
-                    prev.insert(n.0, Some(cur.0));
-                    let nsp = (n.0, alt_dist_to_n);
-                    debug!("Inserting {:?}", nsp);
-                    working_q.push(nsp);
-                    working_set.insert(n.0);
-                } else {
-                    debug!(
-                        "{:?} prev score {} new score {}, no change",
-                        n, n_prev_score, alt_dist_to_n
-                    );
+            }
+            for prev in self.predecessors(&last) {
+                let mut longer = partial.clone();
+                longer.push(*prev);
+                stack.push(longer);
+            }
+        }
+        paths
+    }
+
+    /// Every node on at least one cheapest path to any of the cheapest of
+    /// `targets` (see `best_targets`), ends included. Empty when none of them
+    /// can be reached.
+    pub fn nodes_on_paths_to(&self, targets: &[T]) -> HashSet<T> {
+        let Some((_, reached)) = self.best_targets(targets) else {
+            return HashSet::new();
+        };
+        let mut seen: HashSet<T> = reached.iter().copied().collect();
+        let mut stack = reached;
+        while let Some(cur) = stack.pop() {
+            for prev in self.predecessors(&cur) {
+                if seen.insert(*prev) {
+                    stack.push(*prev);
+                }
+            }
+        }
+        seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::grid::{Grid, Pos};
+
+    /// The 7x7 example from 2024 day 18 after 12 bytes; 22 steps corner to corner.
+    const BYTES_MAZE: &str = "\
+...#...
+..#..#.
+....#..
+...#..#
+..#..#.
+.#..#..
+#.#....";
+
+    /// Every open cell joined to its open neighbours, both ways, at cost 1.
+    fn maze_graph(maze: &str) -> (Grid<bool>, Graph<Pos>) {
+        let grid = Grid::parse(maze, |c| c != '#').unwrap();
+        let mut graph = Graph::new();
+        for pos in grid.find_all(&true) {
+            graph.add_point(pos);
+        }
+        for pos in grid.find_all(&true) {
+            for next in grid.neighbors4(pos).filter(|n| grid[*n]) {
+                graph.add_edge(&pos, &next, 1);
+            }
+        }
+        (grid, graph)
+    }
+
+    fn bfs_distances(grid: &Grid<bool>, start: Pos) -> HashMap<Pos, i32> {
+        let mut distances = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(cur) = queue.pop_front() {
+            for next in grid.neighbors4(cur).filter(|n| grid[*n]) {
+                if !distances.contains_key(&next) {
+                    distances.insert(next, distances[&cur] + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    /// The path really is a walk from `start` to `end` along edges whose
+    /// costs add up to `cost`.
+    fn assert_valid_path<T>(graph: &Graph<T>, start: T, end: T, cost: i32, path: &[T])
+    where
+        T: Hash + Eq + Copy + Clone + core::fmt::Debug + Ord,
+    {
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        let walked: i32 = path
+            .windows(2)
+            .map(|step| {
+                let neighbors = graph.get_neighbors(&step[0]).unwrap_or_default();
+                let edge = neighbors.iter().find(|(n, _)| *n == step[1]);
+                edge.unwrap_or_else(|| panic!("no edge {:?}", step)).1
+            })
+            .sum();
+        assert_eq!(walked, cost);
+    }
+
+    #[test]
+    fn finds_the_known_shortest_path() {
+        let (_, graph) = maze_graph(BYTES_MAZE);
+        let (cost, path) = graph.shortest_path_len(&(0, 0), &(6, 6)).unwrap();
+        assert_eq!(cost, 22);
+        assert_valid_path(&graph, (0, 0), (6, 6), cost, &path);
+    }
+
+    #[test]
+    fn matches_bfs_for_every_reachable_cell() {
+        let (grid, graph) = maze_graph(BYTES_MAZE);
+        let expected = bfs_distances(&grid, (0, 0));
+        for pos in grid.find_all(&true) {
+            let found = graph.shortest_path_len(&(0, 0), &pos);
+            match expected.get(&pos) {
+                Some(distance) => {
+                    let (cost, path) = found.unwrap();
+                    assert_eq!(cost, *distance, "to {:?}", pos);
+                    assert_valid_path(&graph, (0, 0), pos, cost, &path);
+                }
+                None => assert_eq!(found, None, "to {:?}", pos),
+            }
+        }
+    }
+
+    #[test]
+    fn takes_a_cheap_detour_over_an_expensive_edge() {
+        // 'a' -> 'b' directly costs 10, round via 'c' and 'd' it costs 3.
+        // 'b' is queued at 10 first, that entry has to be skipped later.
+        let mut graph = Graph::new();
+        for p in ['a', 'b', 'c', 'd', 'e'] {
+            graph.add_point(p);
+        }
+        graph.add_edge(&'a', &'b', 10);
+        graph.add_edge(&'a', &'c', 1);
+        graph.add_edge(&'c', &'d', 1);
+        graph.add_edge(&'d', &'b', 1);
+        graph.add_edge(&'b', &'e', 2);
+        let (cost, path) = graph.shortest_path_len(&'a', &'e').unwrap();
+        assert_eq!(cost, 5);
+        assert_eq!(path, ['a', 'c', 'd', 'b', 'e']);
+    }
+
+    #[test]
+    fn edges_are_one_way() {
+        let mut graph = Graph::new();
+        graph.add_point(1);
+        graph.add_point(2);
+        graph.add_edge(&1, &2, 4);
+        assert_eq!(graph.shortest_path_len(&1, &2), Some((4, vec![1, 2])));
+        assert_eq!(graph.shortest_path_len(&2, &1), None);
+    }
+
+    #[test]
+    fn walled_off_end_has_no_path() {
+        let (_, graph) = maze_graph("..#.\n.#..\n#...");
+        assert_eq!(graph.shortest_path_len(&(0, 0), &(2, 3)), None);
+    }
+
+    #[test]
+    fn start_is_its_own_shortest_path() {
+        let (_, graph) = maze_graph(BYTES_MAZE);
+        assert_eq!(
+            graph.shortest_path_len(&(3, 3), &(3, 3)),
+            Some((0, vec![(3, 3)]))
+        );
+    }
+
+    #[test]
+    fn distances_agree_with_shortest_path_len() {
+        let (grid, graph) = maze_graph(BYTES_MAZE);
+        let paths = graph.shortest_paths_from(&(0, 0));
+        for pos in grid.find_all(&true) {
+            let single = graph.shortest_path_len(&(0, 0), &pos).map(|(cost, _)| cost);
+            assert_eq!(paths.distance(&pos), single, "to {:?}", pos);
+        }
+        assert_eq!(paths.distances().len(), bfs_distances(&grid, (0, 0)).len());
+    }
+
+    #[test]
+    fn enumerates_every_optimal_path() {
+        // corner to corner of an open 3x3 is 2 rights and 2 downs in any order
+        let (grid, graph) = maze_graph("...\n...\n...");
+        let paths = graph.shortest_paths_from(&(0, 0));
+        let all = paths.paths_to(&(2, 2));
+        assert_eq!(all.len(), 6);
+        for path in &all {
+            assert_valid_path(&graph, (0, 0), (2, 2), 4, path);
+        }
+        let distinct: HashSet<&Vec<Pos>> = all.iter().collect();
+        assert_eq!(distinct.len(), 6);
+        let on_paths = paths.nodes_on_paths_to(&[(2, 2)]);
+        assert_eq!(on_paths.len(), grid.width() * grid.height());
+    }
+
+    #[test]
+    fn dearer_routes_are_left_out() {
+        // two ways round at cost 2, a third at cost 3
+        let mut graph = Graph::new();
+        for p in ['s', 'a', 'b', 'c', 'd', 't'] {
+            graph.add_point(p);
+        }
+        graph.add_edge(&'s', &'a', 1);
+        graph.add_edge(&'a', &'t', 1);
+        graph.add_edge(&'s', &'b', 1);
+        graph.add_edge(&'b', &'t', 1);
+        graph.add_edge(&'s', &'c', 1);
+        graph.add_edge(&'c', &'d', 1);
+        graph.add_edge(&'d', &'t', 1);
+        let paths = graph.shortest_paths_from(&'s');
+        assert_eq!(paths.distance(&'t'), Some(2));
+        let mut preds = paths.predecessors(&'t').to_vec();
+        preds.sort();
+        assert_eq!(preds, ['a', 'b']);
+        let mut all = paths.paths_to(&'t');
+        all.sort();
+        assert_eq!(all, [vec!['s', 'a', 't'], vec!['s', 'b', 't']]);
+        let on_paths = paths.nodes_on_paths_to(&['t']);
+        assert_eq!(on_paths, HashSet::from(['s', 'a', 'b', 't']));
+        assert!(paths.predecessors(&'s').is_empty());
+    }
+
+    #[test]
+    fn only_the_cheapest_targets_count() {
+        let mut graph = Graph::new();
+        for p in 0..5 {
+            graph.add_point(p);
+        }
+        graph.add_edge(&0, &1, 1);
+        graph.add_edge(&1, &2, 1);
+        graph.add_edge(&0, &3, 2);
+        graph.add_edge(&0, &4, 5);
+        let paths = graph.shortest_paths_from(&0);
+        assert_eq!(paths.best_targets(&[2, 3, 4]), Some((2, vec![2, 3])));
+        assert_eq!(
+            paths.nodes_on_paths_to(&[2, 3, 4]),
+            HashSet::from([0, 1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn unreachable_targets_have_no_paths() {
+        let (_, graph) = maze_graph("..#.\n.#..\n#...");
+        let paths = graph.shortest_paths_from(&(0, 0));
+        assert_eq!(paths.distance(&(2, 3)), None);
+        assert!(paths.paths_to(&(2, 3)).is_empty());
+        assert!(paths.nodes_on_paths_to(&[(2, 3)]).is_empty());
+        assert_eq!(paths.best_targets(&[(2, 3)]), None);
+        assert_eq!(paths.paths_to(&(0, 0)), [vec![(0, 0)]]);
+    }
+
+    #[test]
+    fn search_wrappers_agree_with_dijkstra() {
+        let (grid, graph) = maze_graph(BYTES_MAZE);
+        let steps = graph.bfs([(0, 0)]);
+        let manhattan = |p: &Pos| (6 - p.0 + 6 - p.1) as i32;
+        let (cost, path) = graph.astar(&(0, 0), &(6, 6), manhattan).unwrap();
+        assert_eq!(cost, 22);
+        assert_eq!(steps.distance(&(6, 6)), Some(22));
+        assert_valid_path(&graph, (0, 0), (6, 6), cost, &path);
+        for pos in grid.find_all(&true) {
+            let expected = graph.shortest_path_len(&(0, 0), &pos).map(|(c, _)| c);
+            assert_eq!(steps.distance(&pos).map(|d| d as i32), expected);
+        }
+    }
+
+    #[test]
+    fn add_edge_needs_both_points() {
+        let mut graph = Graph::new();
+        graph.add_point('a');
+        assert!(!graph.add_edge(&'a', &'b', 3));
+        assert!(!graph.add_edge(&'b', &'a', 3));
+        assert_eq!(graph.points, HashSet::from(['a']));
+        assert_eq!(graph.edges().count(), 0);
+
+        graph.add_point('b');
+        assert!(graph.add_edge(&'a', &'b', 3));
+        assert!(graph.add_edge(&'a', &'b', 4));
+        assert_eq!(graph.edge_cost(&'a', &'b'), Some(4));
+        assert_eq!(graph.in_degree(&'b'), 1);
+    }
+
+    #[test]
+    fn add_edge_creating_adds_missing_points() {
+        let mut graph = Graph::new();
+        assert_eq!(graph.add_edge_creating(&'a', &'b', 3), None);
+        assert_eq!(graph.points, HashSet::from(['a', 'b']));
+        assert_eq!(graph.add_edge_creating(&'a', &'b', 4), Some(3));
+        assert_eq!(graph.edge_cost(&'a', &'b'), Some(4));
+        assert_eq!(graph.edge_cost(&'b', &'a'), None);
+    }
+
+    #[test]
+    fn undirected_edges_go_both_ways() {
+        let mut graph = Graph::new();
+        graph.add_undirected_edge(&1, &2, 5);
+        graph.add_undirected_edge(&2, &3, 1);
+        let mut edges: Vec<_> = graph.edges().collect();
+        edges.sort();
+        assert_eq!(edges, [(1, 2, 5), (2, 1, 5), (2, 3, 1), (3, 2, 1)]);
+        assert_eq!((graph.degree(&2), graph.in_degree(&2)), (2, 2));
+        assert_eq!(graph.shortest_path_len(&3, &1), Some((6, vec![3, 2, 1])));
+
+        graph.remove_undirected_edge(&1, &2);
+        assert_eq!(graph.edges().count(), 2);
+        assert_eq!((graph.degree(&1), graph.in_degree(&1)), (0, 0));
+        assert_eq!(graph.shortest_path_len(&3, &1), None);
+    }
+
+    #[test]
+    fn removing_a_point_drops_its_edges() {
+        let mut graph = Graph::new();
+        graph.add_edge_creating(&'a', &'b', 1);
+        graph.add_edge_creating(&'b', &'c', 1);
+        graph.add_edge_creating(&'c', &'b', 1);
+        graph.add_edge_creating(&'a', &'c', 5);
+        assert_eq!(graph.in_degree(&'c'), 2);
+        assert!(graph.remove_point(&'b'));
+        assert!(!graph.remove_point(&'b'));
+        assert_eq!(graph.edges().collect::<Vec<_>>(), [('a', 'c', 5)]);
+        assert_eq!((graph.degree(&'a'), graph.in_degree(&'c')), (1, 1));
+        assert_eq!(graph.remove_edge(&'a', &'c'), Some(5));
+        assert_eq!(graph.remove_edge(&'a', &'c'), None);
+        assert_eq!(graph.points, HashSet::from(['a', 'c']));
+    }
+
+    /// The 2024 day 23 example network.
+    const LAN: &str = "kh-tc qp-kh de-cg ka-co yn-aq qp-ub cg-tb vc-aq tb-ka wh-tc yn-cg
+        kh-ub ta-co de-co tc-td tb-wq wh-td ta-ka td-qp aq-cg wq-ub ub-vc de-ta wq-aq wq-vc
+        wh-yn ka-de kh-ta co-tc wh-qp tb-vc td-yn";
+
+    fn lan_graph() -> Graph<&'static str> {
+        let mut graph = Graph::new();
+        for link in LAN.split_whitespace() {
+            let (a, b) = link.split_once('-').unwrap();
+            graph.add_undirected_edge(&a, &b, 1);
+        }
+        graph
+    }
+
+    #[test]
+    fn finds_the_lan_party() {
+        let graph = lan_graph();
+        assert_eq!(graph.maximum_clique(), ["co", "de", "ka", "ta"]);
+        let triangles = graph.k_cliques(3);
+        assert_eq!(triangles.len(), 12);
+        let with_t = triangles
+            .iter()
+            .filter(|t| t.iter().any(|c| c.starts_with('t')));
+        assert_eq!(with_t.count(), 7);
+        assert_eq!(graph.k_cliques(4), [["co", "de", "ka", "ta"]]);
+        assert!(graph.k_cliques(5).is_empty());
+    }
+
+    #[test]
+    fn maximal_cliques_cover_every_point() {
+        let mut graph = Graph::new();
+        // a triangle with a tail, plus a point on its own
+        graph.add_undirected_edge(&1, &2, 1);
+        graph.add_undirected_edge(&2, &3, 1);
+        graph.add_undirected_edge(&1, &3, 1);
+        graph.add_undirected_edge(&3, &4, 1);
+        graph.add_point(5);
+        assert_eq!(
+            graph.maximal_cliques(),
+            [vec![1, 2, 3], vec![3, 4], vec![5]]
+        );
+        assert_eq!(graph.k_cliques(2).len(), 4);
+        assert_eq!(graph.k_cliques(1).len(), 5);
+        assert!(graph.k_cliques(0).is_empty());
+        assert!(Graph::<u8>::new().maximum_clique().is_empty());
+    }
+
+    /// Up to 7 points and some edges between them. Costs start at 1 so there
+    /// are no free cycles, which would give `paths_to` endless paths.
+    fn small_graph() -> impl Strategy<Value = (u8, Vec<(u8, u8, i32)>)> {
+        (1u8..8).prop_flat_map(|n| {
+            let edges = prop::collection::vec((0..n, 0..n, 1i32..20), 0..24);
+            (Just(n), edges)
+        })
+    }
+
+    fn build(n: u8, edges: &[(u8, u8, i32)]) -> Graph<u8> {
+        let mut graph = Graph::new();
+        for point in 0..n {
+            graph.add_point(point);
+        }
+        for (a, b, cost) in edges {
+            graph.add_edge(a, b, *cost);
+        }
+        graph
+    }
+
+    /// All-pairs cheapest costs, `None` where there's no path. A repeated
+    /// edge takes its last cost, the same as `add_edge`.
+    fn floyd_warshall(n: u8, edges: &[(u8, u8, i32)]) -> Vec<Vec<Option<i32>>> {
+        let n = n as usize;
+        let mut dist = vec![vec![None; n]; n];
+        for (a, b, cost) in edges {
+            dist[*a as usize][*b as usize] = Some(*cost);
+        }
+        for (idx, row) in dist.iter_mut().enumerate() {
+            row[idx] = Some(0);
+        }
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    if let (Some(ik), Some(kj)) = (dist[i][k], dist[k][j]) {
+                        if dist[i][j].is_none_or(|ij| ik + kj < ij) {
+                            dist[i][j] = Some(ik + kj);
+                        }
+                    }
                 }
             }
         }
+        dist
+    }
 
-        debug!("Done! Scores: {:?} Prev: {:?}", distances, prev);
+    /// `None` if the path uses an edge the graph doesn't have.
+    fn path_cost(graph: &Graph<u8>, path: &[u8]) -> Option<i32> {
+        path.windows(2)
+            .map(|step| graph.edge_cost(&step[0], &step[1]))
+            .sum()
+    }
 
-        let res = distances.get(end);
-        debug!("Res: {:?}", res);
-        let path = Graph::reconstruct_path(start_ref, end, &prev);
-        if res.is_some() {
-            return (*res.unwrap(), path);
+    proptest! {
+        #[test]
+        fn dijkstra_matches_floyd_warshall((n, edges) in small_graph()) {
+            let graph = build(n, &edges);
+            let expected = floyd_warshall(n, &edges);
+            for start in 0..n {
+                let from_start = graph.shortest_paths_from(&start);
+                for end in 0..n {
+                    let want = expected[start as usize][end as usize];
+                    prop_assert_eq!(from_start.distance(&end), want);
+                    match graph.shortest_path_len(&start, &end) {
+                        Some((cost, path)) => {
+                            prop_assert_eq!(Some(cost), want);
+                            prop_assert_eq!((path[0], path[path.len() - 1]), (start, end));
+                            prop_assert_eq!(path_cost(&graph, &path), Some(cost));
+                        }
+                        None => prop_assert_eq!(want, None),
+                    }
+                    for path in from_start.paths_to(&end) {
+                        prop_assert_eq!(path_cost(&graph, &path), want);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn bfs_matches_floyd_warshall_on_unit_costs((n, edges) in small_graph()) {
+            let edges: Vec<(u8, u8, i32)> = edges.into_iter().map(|(a, b, _)| (a, b, 1)).collect();
+            let graph = build(n, &edges);
+            let expected = floyd_warshall(n, &edges);
+            for start in 0..n {
+                let reached = search::bfs(&graph, [start]);
+                for end in 0..n {
+                    let steps = reached.distance(&end).map(|d| d as i32);
+                    prop_assert_eq!(steps, expected[start as usize][end as usize]);
+                }
+            }
         }
-        (0, Vec::new())
     }
 }
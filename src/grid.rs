@@ -0,0 +1,501 @@
+use core::fmt;
+use std::collections::HashSet;
+use std::ops::{Index, IndexMut};
+
+use crate::components::{self, Components};
+use crate::geom::Direction;
+use crate::graph::{Neighbors, WeightedNeighbors};
+use crate::{AocHelperError, AocResult};
+
+/// `(row, col)`, with `(0, 0)` the top left cell.
+pub type Pos = (usize, usize);
+
+/// A rectangular grid stored row by row in one `Vec`.
+///
+/// Lookups that take a `Pos` come in a checked flavour (`get`, `set`,
+/// `offset`, neighbours) that returns `None` off the edge, and `Index`, which
+/// panics.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Columns become rows.
+    pub fn transpose(&self) -> Self {
+        self.remap(self.height, self.width, |(r, c)| (c, r))
+    }
+
+    /// A quarter turn clockwise: the left column becomes the top row.
+    pub fn rotate_cw(&self) -> Self {
+        let h = self.height;
+        self.remap(self.height, self.width, |(r, c)| (h - 1 - c, r))
+    }
+
+    /// A quarter turn anticlockwise: the top row becomes the left column.
+    pub fn rotate_ccw(&self) -> Self {
+        let w = self.width;
+        self.remap(self.height, self.width, |(r, c)| (c, w - 1 - r))
+    }
+
+    /// Builds a `width` by `height` grid where each cell is copied from
+    /// `source(pos)` in this one.
+    fn remap(&self, width: usize, height: usize, source: impl Fn(Pos) -> Pos) -> Self {
+        let cells = (0..height)
+            .flat_map(|r| (0..width).map(move |c| (r, c)))
+            .map(|pos| self[source(pos)].clone())
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// One row per line, one cell per `char`. Trailing blank lines are
+    /// ignored; rows of different widths are a parse error.
+    pub fn parse(input: &str, mut mapper: impl FnMut(char) -> T) -> AocResult<Self> {
+        Self::try_parse(input, |c| Some(mapper(c)))
+    }
+
+    /// Like `parse`, for mappers that reject some characters: a `None` from
+    /// `mapper` is a parse error pointing at that character.
+    pub fn try_parse(input: &str, mut mapper: impl FnMut(char) -> Option<T>) -> AocResult<Self> {
+        let mut lines: Vec<&str> = input.lines().collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+
+        let width = lines.first().map(|l| l.chars().count()).unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for (row, line) in lines.iter().enumerate() {
+            let mut row_width = 0;
+            for (col, c) in line.chars().enumerate() {
+                let Some(cell) = mapper(c) else {
+                    return Err(AocHelperError::parse_error(
+                        row + 1,
+                        col + 1,
+                        format!("unexpected {:?}", c),
+                    ));
+                };
+                cells.push(cell);
+                row_width += 1;
+            }
+            if row_width != width {
+                return Err(AocHelperError::parse_error(
+                    row + 1,
+                    row_width.min(width) + 1,
+                    format!("row is {} wide, expected {}", row_width, width),
+                ));
+            }
+        }
+        Ok(Self {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, (row, col): Pos) -> bool {
+        row < self.height && col < self.width
+    }
+
+    fn index_of(&self, pos: Pos) -> Option<usize> {
+        self.in_bounds(pos).then(|| pos.0 * self.width + pos.1)
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(|idx| &mut self.cells[idx])
+    }
+
+    /// Returns the old value, or `None` (and changes nothing) off the grid.
+    pub fn set(&mut self, pos: Pos, value: T) -> Option<T> {
+        self.get_mut(pos).map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// `pos` moved by `(d_row, d_col)`, if that is still on the grid.
+    pub fn offset(&self, (row, col): Pos, (d_row, d_col): (isize, isize)) -> Option<Pos> {
+        let next = (
+            row.checked_add_signed(d_row)?,
+            col.checked_add_signed(d_col)?,
+        );
+        self.in_bounds(next).then_some(next)
+    }
+
+    /// One step from `pos` in `dir`, if that is still on the grid.
+    pub fn step(&self, pos: Pos, dir: Direction) -> Option<Pos> {
+        self.offset(pos, dir.delta::<isize>().into())
+    }
+
+    /// The on-grid cells up, right, down and left of `pos`.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::CARDINAL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir))
+    }
+
+    /// `neighbors4` plus the diagonals, clockwise from up.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |r| (0..width).map(move |c| (r, c)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Empty when `row` is off the grid.
+    pub fn row(&self, row: usize) -> impl Iterator<Item = &T> {
+        let range = if row < self.height {
+            row * self.width..(row + 1) * self.width
+        } else {
+            0..0
+        };
+        self.cells[range].iter()
+    }
+
+    /// Empty when `col` is off the grid.
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &T> {
+        let height = if col < self.width { self.height } else { 0 };
+        (0..height).map(move |r| &self.cells[r * self.width + col])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.height).map(move |r| self.row(r))
+    }
+
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |c| self.col(c))
+    }
+
+    /// The cells from `start` stepping by `step` until the edge.
+    pub fn line(&self, start: Pos, step: (isize, isize)) -> impl Iterator<Item = &T> {
+        let first = self.in_bounds(start).then_some(start);
+        std::iter::successors(first, move |&pos| self.offset(pos, step)).map(|pos| &self[pos])
+    }
+
+    /// Every diagonal running down and to the right, starting from the one
+    /// in the bottom left corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let (w, h) = (self.width, self.height);
+        let count = if w == 0 || h == 0 { 0 } else { w + h - 1 };
+        (0..count).map(move |k| {
+            let start = if k < h {
+                (h - 1 - k, 0)
+            } else {
+                (0, k + 1 - h)
+            };
+            self.line(start, (1, 1))
+        })
+    }
+
+    /// Every diagonal running down and to the left, starting from the one
+    /// in the top left corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let (w, h) = (self.width, self.height);
+        let count = if w == 0 || h == 0 { 0 } else { w + h - 1 };
+        (0..count).map(move |k| {
+            let start = if k < w { (0, k) } else { (k + 1 - w, w - 1) };
+            self.line(start, (1, -1))
+        })
+    }
+
+    /// The first cell equal to `value`, row by row.
+    pub fn find(&self, value: &T) -> Option<Pos>
+    where
+        T: PartialEq,
+    {
+        self.find_all(value).next()
+    }
+
+    /// Every cell equal to `value`, row by row.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Pos> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(pos, _)| pos)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Regions of neighbouring cells (up, down, left, right) that `same`
+    /// joins, numbered in reading order.
+    pub fn components(&self, same: impl Fn(&T, &T) -> bool) -> Components<Pos> {
+        let joined = |pos: &Pos| {
+            let pos = *pos;
+            self.neighbors4(pos)
+                .filter(|next| same(&self[pos], &self[*next]))
+                .collect::<Vec<_>>()
+        };
+        components::components(self.positions(), &joined)
+    }
+
+    /// The cells reachable from `start` through neighbours `same` joins,
+    /// `start` first and then in the order they were reached.
+    pub fn flood_fill(&self, start: Pos, same: impl Fn(&T, &T) -> bool) -> Vec<Pos> {
+        let mut seen = vec![start];
+        let mut visited: HashSet<Pos> = HashSet::from([start]);
+        let mut idx = 0;
+        while let Some(&cur) = seen.get(idx) {
+            for next in self.neighbors4(cur) {
+                if same(&self[cur], &self[next]) && visited.insert(next) {
+                    seen.push(next);
+                }
+            }
+            idx += 1;
+        }
+        seen
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!(
+                "{:?} is outside the {}x{} grid",
+                pos, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("{:?} is outside the {}x{} grid", pos, width, height),
+        }
+    }
+}
+
+/// `true` cells are open, and a step goes up, down, left or right from one
+/// open cell to another.
+impl Neighbors<Pos> for Grid<bool> {
+    fn neighbors(&self, pos: &Pos) -> impl Iterator<Item = Pos> {
+        let open = self.get(*pos) == Some(&true);
+        self.neighbors4(*pos).filter(move |n| open && self[*n])
+    }
+}
+
+/// Every step costs 1.
+impl WeightedNeighbors<Pos> for Grid<bool> {
+    fn weighted_neighbors(&self, pos: &Pos) -> impl Iterator<Item = (Pos, i32)> {
+        self.neighbors(pos).map(|n| (n, 1))
+    }
+}
+
+/// One line per row with no trailing newline, so a `Grid<char>` prints back
+/// the way it was parsed.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, row) in self.rows().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: &str = "abc\ndef\n";
+
+    fn small() -> Grid<char> {
+        Grid::parse(SMALL, |c| c).unwrap()
+    }
+
+    fn collect<'a>(cells: impl Iterator<Item = &'a char>) -> String {
+        cells.collect()
+    }
+
+    #[test]
+    fn parses_and_prints_back() {
+        let grid = small();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.to_string(), SMALL.trim_end());
+    }
+
+    #[test]
+    fn trailing_blank_lines_are_ignored() {
+        let grid = Grid::parse("ab\ncd\n\n\n", |c| c).unwrap();
+        assert_eq!(grid.height(), 2);
+    }
+
+    #[test]
+    fn empty_input_is_an_empty_grid() {
+        let grid = Grid::parse("", |c| c).unwrap();
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.diagonals().count(), 0);
+        assert_eq!(grid.anti_diagonals().count(), 0);
+        assert_eq!(grid.to_string(), "");
+    }
+
+    #[test]
+    fn ragged_rows_are_rejected() {
+        match Grid::parse("abc\nde\nfgh", |c| c) {
+            Err(AocHelperError::ParseError { line, column, .. }) => {
+                assert_eq!((line, column), (2, 3))
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn mapper_rejections_point_at_the_cell() {
+        let parsed = Grid::try_parse("12\n3x", |c| c.to_digit(10));
+        match parsed {
+            Err(AocHelperError::ParseError { line, column, .. }) => {
+                assert_eq!((line, column), (2, 2))
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn get_and_set_are_bounds_checked() {
+        let mut grid = small();
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.set((5, 5), 'z'), None);
+        assert_eq!(grid.set((0, 0), 'z'), Some('a'));
+        assert_eq!(grid[(0, 0)], 'z');
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the 3x2 grid")]
+    fn indexing_off_the_grid_panics() {
+        let _ = small()[(2, 0)];
+    }
+
+    #[test]
+    fn rows_cols_and_lines() {
+        let grid = small();
+        assert_eq!(collect(grid.row(1)), "def");
+        assert_eq!(collect(grid.row(2)), "");
+        assert_eq!(collect(grid.col(2)), "cf");
+        assert_eq!(collect(grid.col(3)), "");
+        let rows: Vec<String> = grid.rows().map(collect).collect();
+        assert_eq!(rows, ["abc", "def"]);
+        let cols: Vec<String> = grid.cols().map(collect).collect();
+        assert_eq!(cols, ["ad", "be", "cf"]);
+        assert_eq!(collect(grid.line((1, 2), (0, -1))), "fed");
+        assert_eq!(collect(grid.line((2, 0), (0, 1))), "");
+    }
+
+    #[test]
+    fn diagonals_cover_every_cell_once() {
+        let grid = small();
+        let diagonals: Vec<String> = grid.diagonals().map(collect).collect();
+        assert_eq!(diagonals, ["d", "ae", "bf", "c"]);
+        let anti: Vec<String> = grid.anti_diagonals().map(collect).collect();
+        assert_eq!(anti, ["a", "bd", "ce", "f"]);
+    }
+
+    #[test]
+    fn zero_width_grids_have_no_diagonals() {
+        let grid = Grid::new(0, 3, 'x');
+        assert_eq!(grid.diagonals().count(), 0);
+        assert_eq!(grid.anti_diagonals().count(), 0);
+    }
+
+    #[test]
+    fn zero_height_grids_have_no_diagonals() {
+        let grid = Grid::new(3, 0, 'x');
+        assert_eq!(grid.diagonals().count(), 0);
+        assert_eq!(grid.anti_diagonals().count(), 0);
+    }
+
+    #[test]
+    fn neighbours_stay_on_the_grid() {
+        let grid = small();
+        let corner: Vec<Pos> = grid.neighbors4((0, 0)).collect();
+        assert_eq!(corner, [(0, 1), (1, 0)]);
+        let middle: Vec<Pos> = grid.neighbors4((1, 1)).collect();
+        assert_eq!(middle, [(0, 1), (1, 2), (1, 0)]);
+        let top: Vec<Pos> = grid.neighbors8((0, 1)).collect();
+        assert_eq!(top, [(0, 2), (1, 2), (1, 1), (1, 0), (0, 0)]);
+        assert_eq!(grid.step((1, 2), Direction::Right), None);
+        assert_eq!(grid.step((1, 2), Direction::UpLeft), Some((0, 1)));
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((0, 0), (1, 2)), Some((1, 2)));
+    }
+
+    #[test]
+    fn finds_cells() {
+        let grid = Grid::parse("#.#\n.#.", |c| c).unwrap();
+        let walls: Vec<Pos> = grid.find_all(&'#').collect();
+        assert_eq!(walls, [(0, 0), (0, 2), (1, 1)]);
+        assert_eq!(grid.find(&'.'), Some((0, 1)));
+        assert_eq!(grid.find(&'x'), None);
+    }
+
+    #[test]
+    fn transposes_and_rotates() {
+        let grid = small();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), grid);
+    }
+
+    #[test]
+    fn maps_cells() {
+        let digits = Grid::try_parse("12\n34", |c| c.to_digit(10)).unwrap();
+        let doubled = digits.map(|d| d * 2);
+        assert_eq!(doubled.to_string(), "24\n68");
+        let iterated: Vec<(Pos, u32)> = doubled.iter().map(|(p, d)| (p, *d)).collect();
+        assert_eq!(iterated[3], ((1, 1), 8));
+    }
+}
@@ -1,144 +1,562 @@
 use core::fmt;
+use std::convert::Infallible;
 use std::env;
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
 
-use tracing::{error, info, Level};
+use tracing::{debug, error, info};
 
+use crate::answers::{AnswerStatus, Answers};
+use crate::provider::{CachingProvider, InputProvider};
+use crate::solution::Part;
+
+pub mod answers;
+pub mod bench;
+pub mod components;
 pub mod counter;
+pub mod error;
+pub mod geom;
 pub mod graph;
-pub mod map_vec_extend;
+pub mod grid;
+pub mod logging;
+pub mod multimap;
+pub mod parse;
+pub mod provider;
+pub mod regression;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+pub mod solution;
+pub mod timeout;
+
+pub use error::{parse_token, parse_token_in, AocHelperError, AocResult};
 
 #[derive(Debug, Clone)]
 pub enum RequestedAocInputType {
     Real,
     Test,
-    CustomTest { fname: String },
+    CustomTest {
+        fname: String,
+    },
+    /// Read once from standard input, every later request gets the same text.
+    Stdin,
+    /// Any file, outside the day's directory. Treated like a real input.
+    Path(PathBuf),
 }
 
-pub struct AocHelper {
-    _day: u32,
-    test_inputs: Vec<PathBuf>,
-    real_input: PathBuf,
+impl RequestedAocInputType {
+    /// True for the small example inputs from the puzzle text.
+    pub fn is_example(&self) -> bool {
+        match self {
+            RequestedAocInputType::Real
+            | RequestedAocInputType::Stdin
+            | RequestedAocInputType::Path(_) => false,
+            RequestedAocInputType::Test | RequestedAocInputType::CustomTest { .. } => true,
+        }
+    }
+
+    /// Name of the input file in the day's directory, also the key used in
+    /// `answers.toml`. `None` for inputs from outside the day's directory.
+    pub fn file_name(&self) -> Option<&str> {
+        match self {
+            RequestedAocInputType::Real => Some("real_input"),
+            RequestedAocInputType::Test => Some("test_input"),
+            RequestedAocInputType::CustomTest { fname } => Some(fname.as_str()),
+            RequestedAocInputType::Stdin | RequestedAocInputType::Path(_) => None,
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
-pub enum AocHelperError {
-    FileReadError,
-    TimeoutError,
+impl fmt::Display for RequestedAocInputType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestedAocInputType::Real => write!(f, "real"),
+            RequestedAocInputType::Test => write!(f, "test"),
+            RequestedAocInputType::CustomTest { fname } => write!(f, "{}", fname),
+            RequestedAocInputType::Stdin => write!(f, "stdin"),
+            RequestedAocInputType::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
 }
 
-pub type AocResult<T> = std::result::Result<T, AocHelperError>;
+impl FromStr for RequestedAocInputType {
+    type Err = Infallible;
 
-impl fmt::Display for AocHelperError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            AocHelperError::FileReadError => {
-                write!(f, "File read error! Could not read file!")
-            }
-            AocHelperError::TimeoutError => {
-                write!(f, "Timed out!!")
-            }
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "real" => RequestedAocInputType::Real,
+            "test" => RequestedAocInputType::Test,
+            "-" => RequestedAocInputType::Stdin,
+            fname => RequestedAocInputType::CustomTest {
+                fname: fname.to_string(),
+            },
+        })
     }
 }
 
+/// Days that have a puzzle.
+pub const DAYS: std::ops::RangeInclusive<u32> = 1..=25;
+
+/// Test inputs are the files in a day's directory whose names start with this.
+pub const TEST_INPUT_PREFIX: &str = "test_input";
+
+/// Env var (or `.env` key) pointing at the directory holding `dayN/` inputs.
+pub const BASE_PATH_KEY: &str = "STATIC_BASE_PATH";
+
+pub struct AocHelper {
+    day: u32,
+    test_inputs: Vec<PathBuf>,
+    real_input: PathBuf,
+    answers_path: PathBuf,
+    answers: Answers,
+    provider: Box<dyn InputProvider>,
+}
+
 impl AocHelper {
-    fn setup_logging() {
-        let subscriber = tracing_subscriber::FmtSubscriber::builder()
-            // .with_max_level(Level::ERROR)
-            // .with_max_level(Level::INFO)
-            .with_max_level(Level::DEBUG)
-            .with_thread_ids(true)
-            .with_thread_names(true)
-            .with_ansi(false)
-            .with_ansi(true)
-            // .pretty()
-            .finish();
-
-        let _ = tracing::subscriber::set_global_default(subscriber);
-    }
-
-    pub fn new(day: u32, addl_test_inputs: Option<Vec<String>>) -> Self {
-        AocHelper::setup_logging();
-        let _ = dotenvy::dotenv();
-        let base_path: String = env::var("STATIC_BASE_PATH").unwrap();
-        let mut day_input: PathBuf = PathBuf::new();
-        day_input.push(base_path);
+    /// Finds the inputs directory with `find_base_path` and points at
+    /// `dayN/` inside it.
+    ///
+    /// Real inputs come from `provider::CachingProvider::from_env`: files on
+    /// disk, fetched and stored there first when `AOC_SESSION` is set.
+    ///
+    /// Also sets up logging from the environment the first time it's called,
+    /// see `logging::LogConfig` (and `logging::disable_auto_init` to opt out).
+    pub fn new(day: u32, addl_test_inputs: Option<Vec<String>>) -> AocResult<Self> {
+        AocHelper::with_base_path(None, day, addl_test_inputs)
+    }
+
+    /// Like `new`, but `base_path` (when given) wins over any other setting.
+    pub fn with_base_path(
+        base_path: Option<&Path>,
+        day: u32,
+        addl_test_inputs: Option<Vec<String>>,
+    ) -> AocResult<Self> {
+        logging::init_from_env();
+        if !DAYS.contains(&day) {
+            return Err(AocHelperError::InvalidDay { day });
+        }
+        let base_path = AocHelper::find_base_path(base_path)?;
+        let provider = Box::new(CachingProvider::from_env(&base_path));
+        let mut day_input: PathBuf = base_path;
         day_input.push(format!("day{}", day));
 
         let mut real_input: PathBuf = day_input.clone();
         real_input.push("real_input");
 
+        let mut answers_path: PathBuf = day_input.clone();
+        answers_path.push("answers.toml");
+        let answers = Answers::load(&answers_path);
+
+        // `test_input` always comes first, whether it exists or not
         let mut test_inputs: Vec<PathBuf> = Vec::new();
         let mut default_test_input: PathBuf = day_input.clone();
-        default_test_input.push("test_input");
+        default_test_input.push(TEST_INPUT_PREFIX);
         test_inputs.push(default_test_input);
+        test_inputs.extend(AocHelper::discover_test_inputs(&day_input));
 
         match addl_test_inputs {
             Some(input_fnames) => {
                 input_fnames.into_iter().for_each(|fname| {
                     let mut new_test_path: PathBuf = day_input.clone();
                     new_test_path.push(fname);
-                    test_inputs.push(new_test_path);
+                    if !test_inputs.contains(&new_test_path) {
+                        test_inputs.push(new_test_path);
+                    }
                 });
             }
             None => {}
         };
 
-        AocHelper {
-            _day: day,
+        Ok(AocHelper {
+            day,
             test_inputs: test_inputs,
             real_input: real_input,
+            answers_path,
+            answers,
+            provider,
+        })
+    }
+
+    /// Swaps where real inputs come from.
+    pub fn with_provider(mut self, provider: Box<dyn InputProvider>) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    /// Where the `dayN/` input directories live. The first of these wins:
+    ///
+    /// 1. `explicit`, which must exist
+    /// 2. `STATIC_BASE_PATH` in the environment
+    /// 3. `STATIC_BASE_PATH` in `.env`
+    /// 4. `static/` at the workspace root
+    ///
+    /// Settings 2-4 are skipped when they don't point at a directory.
+    pub fn find_base_path(explicit: Option<&Path>) -> AocResult<PathBuf> {
+        AocHelper::find_base_path_from(
+            explicit,
+            env::var(BASE_PATH_KEY).ok(),
+            dotenvy::dotenv_iter(),
+            Path::new(env!("CARGO_MANIFEST_DIR")),
+        )
+    }
+
+    /// `find_base_path` with the environment's setting, the `.env` file and
+    /// the workspace root passed in.
+    fn find_base_path_from(
+        explicit: Option<&Path>,
+        from_env: Option<String>,
+        dotenv: dotenvy::Result<dotenvy::Iter<File>>,
+        workspace: &Path,
+    ) -> AocResult<PathBuf> {
+        if let Some(path) = explicit {
+            if path.is_dir() {
+                return Ok(path.to_path_buf());
+            }
+            return Err(AocHelperError::NoInputDir {
+                tried: vec![path.to_path_buf()],
+            });
+        }
+
+        let mut candidates: Vec<PathBuf> = Vec::new();
+        if let Some(path) = from_env {
+            candidates.push(PathBuf::from(path));
+        }
+        if let Ok(vars) = dotenv {
+            let from_dotenv = vars
+                .filter_map(|var| var.ok())
+                .find(|(key, _)| key == BASE_PATH_KEY);
+            if let Some((_, path)) = from_dotenv {
+                candidates.push(PathBuf::from(path));
+            }
+        }
+        candidates.push(workspace.join("static"));
+
+        for candidate in candidates.iter() {
+            if candidate.is_dir() {
+                debug!("Using inputs from {}", candidate.display());
+                return Ok(candidate.clone());
+            }
+            debug!("No inputs directory at {}", candidate.display());
         }
+        Err(AocHelperError::NoInputDir { tried: candidates })
+    }
+
+    /// Every `test_input*` file in `day_dir` other than `test_input` itself,
+    /// sorted by name.
+    fn discover_test_inputs(day_dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(day_dir) else {
+            return Vec::new();
+        };
+        let mut found: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name.starts_with(TEST_INPUT_PREFIX) && name != TEST_INPUT_PREFIX
+                    })
+            })
+            .collect();
+        found.sort();
+        found
+    }
+
+    /// Every test input that exists on disk, by name: `test_input` first, then
+    /// the other `test_input*` files and any extra tests this helper was given.
+    pub fn test_cases(&self) -> impl Iterator<Item = RequestedAocInputType> + '_ {
+        self.test_inputs
+            .iter()
+            .enumerate()
+            .filter(|(_, path)| path.is_file())
+            .filter_map(|(idx, path)| {
+                if idx == 0 {
+                    return Some(RequestedAocInputType::Test);
+                }
+                let fname = path.file_name()?.to_string_lossy().to_string();
+                Some(RequestedAocInputType::CustomTest { fname })
+            })
+    }
+
+    /// The inputs this helper knows about that exist on disk: every test
+    /// case, then the real input.
+    pub fn available_inputs(&self) -> Vec<RequestedAocInputType> {
+        let mut inputs: Vec<RequestedAocInputType> = self.test_cases().collect();
+        if self.real_input.is_file() {
+            inputs.push(RequestedAocInputType::Real);
+        }
+        inputs
+    }
+
+    pub fn get_expected_answer(
+        &self,
+        input_type: &RequestedAocInputType,
+        part: Part,
+    ) -> Option<&str> {
+        self.answers.get(input_type.file_name()?, part)
+    }
+
+    pub fn check_answer(
+        &self,
+        input_type: &RequestedAocInputType,
+        part: Part,
+        answer: &str,
+    ) -> AnswerStatus {
+        match input_type.file_name() {
+            Some(input_name) => self.answers.check(input_name, part, answer),
+            None => AnswerStatus::Unknown,
+        }
+    }
+
+    /// Whether the real input is on disk or can be fetched.
+    pub fn has_real_input(&self) -> bool {
+        self.provider.has_input(self.day)
     }
 
     pub fn get_real_input_path(&self) -> PathBuf {
         self.real_input.clone()
     }
 
+    pub fn get_answers_path(&self) -> PathBuf {
+        self.answers_path.clone()
+    }
+
     pub fn get_test_input_path(&self, fname: Option<&str>) -> Option<PathBuf> {
         if let Some(target) = fname {
             return self
                 .test_inputs
                 .iter()
-                .find(|buf| buf.ends_with(target))
+                .find(|buf| buf.file_name().is_some_and(|name| name == target))
                 .cloned();
         }
         return self.test_inputs.iter().next().cloned();
     }
 
     fn get_fname_as_string(fname: &PathBuf) -> AocResult<String> {
-        let data = std::fs::read_to_string(&fname);
-        match data {
-            Ok(d) => return Ok(d),
-            Err(e) => {
-                error!(
-                    "Failed to read file {}, err: {}",
-                    fname.to_str().unwrap_or("BAD_FNAME"),
-                    e
-                );
-                return Err(AocHelperError::FileReadError);
+        std::fs::read_to_string(fname).map_err(|e| {
+            error!("Failed to read file {}, err: {}", fname.display(), e);
+            AocHelperError::FileReadError {
+                path: fname.clone(),
+                source: e,
             }
+        })
+    }
+
+    /// Standard input can only be read once, so it's kept for every later
+    /// part and day that asks for it.
+    fn get_stdin_as_string() -> AocResult<String> {
+        static STDIN: OnceLock<String> = OnceLock::new();
+        if let Some(input) = STDIN.get() {
+            return Ok(input.clone());
         }
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| AocHelperError::FileReadError {
+                path: PathBuf::from("<stdin>"),
+                source: e,
+            })?;
+        Ok(STDIN.get_or_init(|| input).clone())
     }
 
     pub fn get_input_as_string(&self, input_type: RequestedAocInputType) -> AocResult<String> {
         match input_type {
-            RequestedAocInputType::Real => {
-                return AocHelper::get_fname_as_string(&self.real_input);
-            }
-            RequestedAocInputType::Test => {
-                return AocHelper::get_fname_as_string(&self.test_inputs.iter().next().unwrap());
-            }
+            RequestedAocInputType::Real => self.provider.real_input(self.day),
+            RequestedAocInputType::Test => match self.test_inputs.first() {
+                Some(path) => AocHelper::get_fname_as_string(path),
+                None => Err(AocHelperError::NoTestInput { day: self.day }),
+            },
             RequestedAocInputType::CustomTest { fname } => {
-                let fname = self.get_test_input_path(Some(fname.as_str()));
-                info!("Working on fname: {:?}", fname);
-                if let Some(pb) = fname {
-                    return AocHelper::get_fname_as_string(&pb);
+                let path = self.get_test_input_path(Some(fname.as_str()));
+                info!("Working on fname: {:?}", path);
+                match path {
+                    Some(pb) => AocHelper::get_fname_as_string(&pb),
+                    None => Err(AocHelperError::UnknownCustomTest { fname }),
                 }
             }
+            RequestedAocInputType::Stdin => AocHelper::get_stdin_as_string(),
+            RequestedAocInputType::Path(path) => AocHelper::get_fname_as_string(&path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// A scratch directory with `env/`, `dotenv/` and `workspace/static/`
+    /// input directories and a `.env` pointing at `dotenv/`.
+    struct Layers {
+        root: PathBuf,
+    }
+
+    impl Layers {
+        fn new(name: &str) -> Self {
+            let root = env::temp_dir().join(format!("aoc2024-lib-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+            for dir in ["env", "dotenv", "workspace/static"] {
+                fs::create_dir_all(root.join(dir)).unwrap();
+            }
+            let dotenv = format!(
+                "OTHER=1\n{}={}\n",
+                BASE_PATH_KEY,
+                root.join("dotenv").display()
+            );
+            fs::write(root.join(".env"), dotenv).unwrap();
+            Self { root }
+        }
+
+        fn path(&self, name: &str) -> PathBuf {
+            self.root.join(name)
+        }
+
+        fn env(&self, name: &str) -> Option<String> {
+            Some(self.path(name).display().to_string())
+        }
+
+        fn dotenv(&self) -> dotenvy::Result<dotenvy::Iter<File>> {
+            dotenvy::from_path_iter(self.path(".env"))
         }
 
-        Err(AocHelperError::FileReadError)
+        fn find(
+            &self,
+            explicit: Option<&Path>,
+            from_env: Option<String>,
+            dotenv: dotenvy::Result<dotenvy::Iter<File>>,
+        ) -> AocResult<PathBuf> {
+            AocHelper::find_base_path_from(explicit, from_env, dotenv, &self.path("workspace"))
+        }
+    }
+
+    impl Drop for Layers {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn discovers_numbered_test_inputs_in_order() {
+        let layers = Layers::new("discover");
+        let day = layers.path("env/day1");
+        fs::create_dir_all(day.join("test_input_dir")).unwrap();
+        for name in [
+            "test_input3",
+            "real_input",
+            "test_input",
+            "answers.toml",
+            "test_input2",
+            "my_test_input",
+            "test_input_b",
+        ] {
+            fs::write(day.join(name), "").unwrap();
+        }
+        let names: Vec<String> = AocHelper::discover_test_inputs(&day)
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["test_input2", "test_input3", "test_input_b"]);
+
+        let helper = AocHelper::with_base_path(Some(&layers.path("env")), 1, None).unwrap();
+        let cases: Vec<String> = helper.test_cases().map(|input| input.to_string()).collect();
+        assert_eq!(
+            cases,
+            ["test", "test_input2", "test_input3", "test_input_b"]
+        );
+    }
+
+    #[test]
+    fn no_test_input_is_an_error() {
+        let layers = Layers::new("no-test-input");
+        let mut helper = AocHelper::with_base_path(Some(&layers.path("env")), 3, None).unwrap();
+        helper.test_inputs.clear();
+        match helper.get_input_as_string(RequestedAocInputType::Test) {
+            Err(AocHelperError::NoTestInput { day: 3 }) => {}
+            other => panic!("expected NoTestInput, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn discovers_nothing_in_an_empty_or_missing_dir() {
+        let layers = Layers::new("discover-empty");
+        assert!(AocHelper::discover_test_inputs(&layers.path("env")).is_empty());
+        assert!(AocHelper::discover_test_inputs(&layers.path("missing")).is_empty());
+    }
+
+    #[test]
+    fn explicit_path_wins() {
+        let layers = Layers::new("explicit");
+        let explicit = layers.path("env");
+        let found = layers.find(Some(&explicit), layers.env("dotenv"), layers.dotenv());
+        assert_eq!(found.unwrap(), explicit);
+
+        // and has to exist, nothing else is tried
+        let missing = layers.path("missing");
+        match layers.find(Some(&missing), layers.env("env"), layers.dotenv()) {
+            Err(AocHelperError::NoInputDir { tried }) => assert_eq!(tried, [missing]),
+            other => panic!("expected NoInputDir, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn environment_beats_dotenv() {
+        let layers = Layers::new("env");
+        let found = layers.find(None, layers.env("env"), layers.dotenv());
+        assert_eq!(found.unwrap(), layers.path("env"));
+    }
+
+    #[test]
+    fn dotenv_beats_workspace() {
+        let layers = Layers::new("dotenv");
+        assert_eq!(
+            layers.find(None, None, layers.dotenv()).unwrap(),
+            layers.path("dotenv")
+        );
+        // an environment setting that isn't a directory is passed over
+        assert_eq!(
+            layers
+                .find(None, layers.env("missing"), layers.dotenv())
+                .unwrap(),
+            layers.path("dotenv")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_workspace_static() {
+        let layers = Layers::new("workspace");
+        let no_dotenv = dotenvy::from_path_iter(layers.path("no.env"));
+        assert_eq!(
+            layers.find(None, None, no_dotenv).unwrap(),
+            layers.path("workspace/static")
+        );
+        // a .env without the key is no help either
+        fs::write(layers.path(".env"), "OTHER=1\n").unwrap();
+        assert_eq!(
+            layers.find(None, None, layers.dotenv()).unwrap(),
+            layers.path("workspace/static")
+        );
+    }
+
+    #[test]
+    fn nothing_found_lists_every_candidate() {
+        let layers = Layers::new("nothing");
+        fs::remove_dir(layers.path("dotenv")).unwrap();
+        fs::remove_dir(layers.path("workspace/static")).unwrap();
+        match layers.find(None, layers.env("missing"), layers.dotenv()) {
+            Err(AocHelperError::NoInputDir { tried }) => assert_eq!(
+                tried,
+                [
+                    layers.path("missing"),
+                    layers.path("dotenv"),
+                    layers.path("workspace/static")
+                ]
+            ),
+            other => panic!("expected NoInputDir, got {:?}", other),
+        }
     }
 }
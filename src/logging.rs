@@ -0,0 +1,212 @@
+use std::env;
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, Once};
+
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::{AocHelperError, AocResult};
+
+/// Filter used when `AOC_LOG` isn't set.
+pub const DEFAULT_FILTER: &str = "info";
+
+static AUTO_INIT: AtomicBool = AtomicBool::new(true);
+static INIT: Once = Once::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+/// Logging settings, read from the environment (or `.env`):
+///
+/// - `AOC_LOG`: level and per-target filters, e.g. `debug,aoc2024::graph=warn`.
+///   Same syntax as `RUST_LOG`; `off` silences everything.
+/// - `AOC_LOG_FORMAT`: `text` (default) or `json`.
+/// - `AOC_LOG_ANSI`: `true`/`false`, colours for text output. Defaults to on,
+///   always off when writing to a file.
+/// - `AOC_LOG_FILE`: append to this file instead of stdout.
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub filter: String,
+    pub format: LogFormat,
+    pub ansi: bool,
+    pub file: Option<PathBuf>,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            filter: DEFAULT_FILTER.to_string(),
+            format: LogFormat::Text,
+            ansi: true,
+            file: None,
+        }
+    }
+}
+
+impl LogConfig {
+    pub fn from_env() -> AocResult<Self> {
+        let _ = dotenvy::dotenv();
+        LogConfig::from_env_with(|key| env::var(key).ok())
+    }
+
+    /// `from_env` with variables looked up by `var`.
+    fn from_env_with(var: impl Fn(&str) -> Option<String>) -> AocResult<Self> {
+        let mut config = LogConfig::default();
+        if let Some(filter) = var("AOC_LOG") {
+            config.filter = filter;
+        }
+        if let Some(format) = var("AOC_LOG_FORMAT") {
+            config.format = match format.trim().to_ascii_lowercase().as_str() {
+                "text" => LogFormat::Text,
+                "json" => LogFormat::Json,
+                _ => {
+                    return Err(AocHelperError::config_error(
+                        "AOC_LOG_FORMAT",
+                        "expected `text` or `json`",
+                    ))
+                }
+            };
+        }
+        if let Some(ansi) = var("AOC_LOG_ANSI") {
+            config.ansi = match ansi.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "on" | "yes" => true,
+                "0" | "false" | "off" | "no" => false,
+                _ => {
+                    return Err(AocHelperError::config_error(
+                        "AOC_LOG_ANSI",
+                        "expected `true` or `false`",
+                    ))
+                }
+            };
+        }
+        if let Some(file) = var("AOC_LOG_FILE") {
+            config.file = Some(PathBuf::from(file));
+        }
+        Ok(config)
+    }
+
+    /// Installs this config as the global subscriber. Does nothing if one is
+    /// already installed.
+    pub fn install(&self) -> AocResult<()> {
+        let filter = EnvFilter::try_new(&self.filter)
+            .map_err(|e| AocHelperError::config_error("AOC_LOG", e))?;
+
+        let (writer, ansi) = match &self.file {
+            Some(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|source| AocHelperError::FileWriteError {
+                        path: path.clone(),
+                        source,
+                    })?;
+                (BoxMakeWriter::new(Mutex::new(file)), false)
+            }
+            None => (BoxMakeWriter::new(std::io::stdout), self.ansi),
+        };
+
+        let layer = tracing_subscriber::fmt::layer()
+            .with_thread_ids(true)
+            .with_thread_names(true)
+            .with_ansi(ansi)
+            .with_writer(writer);
+        let layer = match self.format {
+            LogFormat::Text => layer.boxed(),
+            LogFormat::Json => layer.json().boxed(),
+        };
+
+        let subscriber = tracing_subscriber::registry().with(layer).with(filter);
+        let _ = tracing::subscriber::set_global_default(subscriber);
+        Ok(())
+    }
+}
+
+/// Stops `AocHelper::new` from installing a global subscriber, for callers
+/// that set up their own. Call it before the first `AocHelper` is built.
+pub fn disable_auto_init() {
+    AUTO_INIT.store(false, Ordering::SeqCst);
+}
+
+/// What `AocHelper::new` calls: installs the config from the environment the
+/// first time round, unless `disable_auto_init` was called.
+pub fn init_from_env() {
+    if !AUTO_INIT.load(Ordering::SeqCst) {
+        return;
+    }
+    INIT.call_once(|| {
+        let result = LogConfig::from_env().and_then(|config| config.install());
+        if let Err(e) = result {
+            eprintln!("Logging not set up: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn config(vars: &[(&str, &str)]) -> AocResult<LogConfig> {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        LogConfig::from_env_with(|key| vars.get(key).map(|v| v.to_string()))
+    }
+
+    fn bad_key(result: AocResult<LogConfig>) -> String {
+        match result {
+            Err(AocHelperError::ConfigError { key, .. }) => key,
+            other => panic!("expected ConfigError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn defaults_when_nothing_is_set() {
+        let config = config(&[]).unwrap();
+        assert_eq!(config.filter, DEFAULT_FILTER);
+        assert_eq!(config.format, LogFormat::Text);
+        assert!(config.ansi);
+        assert_eq!(config.file, None);
+    }
+
+    #[test]
+    fn reads_every_setting() {
+        let config = config(&[
+            ("AOC_LOG", "debug,aoc2024::graph=warn"),
+            ("AOC_LOG_FORMAT", " JSON "),
+            ("AOC_LOG_ANSI", "off"),
+            ("AOC_LOG_FILE", "/tmp/aoc.log"),
+        ])
+        .unwrap();
+        assert_eq!(config.filter, "debug,aoc2024::graph=warn");
+        assert_eq!(config.format, LogFormat::Json);
+        assert!(!config.ansi);
+        assert_eq!(config.file, Some(PathBuf::from("/tmp/aoc.log")));
+    }
+
+    #[test]
+    fn ansi_spellings() {
+        for (raw, ansi) in [("1", true), ("Yes", true), ("false", false), ("no", false)] {
+            assert_eq!(config(&[("AOC_LOG_ANSI", raw)]).unwrap().ansi, ansi);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_values() {
+        assert_eq!(
+            bad_key(config(&[("AOC_LOG_FORMAT", "xml")])),
+            "AOC_LOG_FORMAT"
+        );
+        assert_eq!(
+            bad_key(config(&[("AOC_LOG_ANSI", "maybe")])),
+            "AOC_LOG_ANSI"
+        );
+        assert_eq!(bad_key(config(&[("AOC_LOG_FORMAT", "")])), "AOC_LOG_FORMAT");
+    }
+}
@@ -0,0 +1,517 @@
+use std::collections::{btree_map, hash_map, BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::Index;
+
+/// The collection holding one key's values in a `MultiMap`: a `Vec` keeps
+/// duplicates and insertion order, the sets don't.
+pub trait Bucket: Default {
+    type Item;
+    type Iter<'a>: Iterator<Item = &'a Self::Item>
+    where
+        Self: 'a;
+
+    /// Adds `v`, returning `false` if a set already had it.
+    fn put(&mut self, v: Self::Item) -> bool;
+    /// Removes one copy of `v`, returning whether there was one.
+    fn take(&mut self, v: &Self::Item) -> bool;
+    fn contains(&self, v: &Self::Item) -> bool;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+impl<V: PartialEq> Bucket for Vec<V> {
+    type Item = V;
+    type Iter<'a>
+        = std::slice::Iter<'a, V>
+    where
+        V: 'a;
+
+    fn put(&mut self, v: V) -> bool {
+        self.push(v);
+        true
+    }
+
+    fn take(&mut self, v: &V) -> bool {
+        match self.iter().position(|x| x == v) {
+            Some(idx) => {
+                self.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn contains(&self, v: &V) -> bool {
+        <[V]>::contains(self, v)
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        <[V]>::iter(self)
+    }
+}
+
+impl<V: Hash + Eq> Bucket for HashSet<V> {
+    type Item = V;
+    type Iter<'a>
+        = std::collections::hash_set::Iter<'a, V>
+    where
+        V: 'a;
+
+    fn put(&mut self, v: V) -> bool {
+        self.insert(v)
+    }
+
+    fn take(&mut self, v: &V) -> bool {
+        self.remove(v)
+    }
+
+    fn contains(&self, v: &V) -> bool {
+        HashSet::contains(self, v)
+    }
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        HashSet::iter(self)
+    }
+}
+
+impl<V: Ord> Bucket for BTreeSet<V> {
+    type Item = V;
+    type Iter<'a>
+        = std::collections::btree_set::Iter<'a, V>
+    where
+        V: 'a;
+
+    fn put(&mut self, v: V) -> bool {
+        self.insert(v)
+    }
+
+    fn take(&mut self, v: &V) -> bool {
+        self.remove(v)
+    }
+
+    fn contains(&self, v: &V) -> bool {
+        BTreeSet::contains(self, v)
+    }
+
+    fn len(&self) -> usize {
+        BTreeSet::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BTreeSet::iter(self)
+    }
+}
+
+/// The map behind a `MultiMap`: `BTreeMap` for keys kept in order, `HashMap`
+/// otherwise.
+pub trait BucketMap: Default {
+    type Key;
+    type Bucket: Bucket;
+    type Iter<'a>: Iterator<Item = (&'a Self::Key, &'a Self::Bucket)>
+    where
+        Self: 'a;
+    type IntoIter: Iterator<Item = (Self::Key, Self::Bucket)>;
+
+    fn get(&self, k: &Self::Key) -> Option<&Self::Bucket>;
+    fn get_mut(&mut self, k: &Self::Key) -> Option<&mut Self::Bucket>;
+    /// The bucket for `k`, created empty if there isn't one.
+    fn bucket(&mut self, k: Self::Key) -> &mut Self::Bucket;
+    fn remove(&mut self, k: &Self::Key) -> Option<Self::Bucket>;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    fn iter(&self) -> Self::Iter<'_>;
+    fn into_iter(self) -> Self::IntoIter;
+}
+
+impl<K: Ord, C: Bucket> BucketMap for BTreeMap<K, C> {
+    type Key = K;
+    type Bucket = C;
+    type Iter<'a>
+        = btree_map::Iter<'a, K, C>
+    where
+        K: 'a,
+        C: 'a;
+    type IntoIter = btree_map::IntoIter<K, C>;
+
+    fn get(&self, k: &K) -> Option<&C> {
+        BTreeMap::get(self, k)
+    }
+
+    fn get_mut(&mut self, k: &K) -> Option<&mut C> {
+        BTreeMap::get_mut(self, k)
+    }
+
+    fn bucket(&mut self, k: K) -> &mut C {
+        self.entry(k).or_default()
+    }
+
+    fn remove(&mut self, k: &K) -> Option<C> {
+        BTreeMap::remove(self, k)
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BTreeMap::iter(self)
+    }
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self)
+    }
+}
+
+impl<K: Hash + Eq, C: Bucket> BucketMap for HashMap<K, C> {
+    type Key = K;
+    type Bucket = C;
+    type Iter<'a>
+        = hash_map::Iter<'a, K, C>
+    where
+        K: 'a,
+        C: 'a;
+    type IntoIter = hash_map::IntoIter<K, C>;
+
+    fn get(&self, k: &K) -> Option<&C> {
+        HashMap::get(self, k)
+    }
+
+    fn get_mut(&mut self, k: &K) -> Option<&mut C> {
+        HashMap::get_mut(self, k)
+    }
+
+    fn bucket(&mut self, k: K) -> &mut C {
+        self.entry(k).or_default()
+    }
+
+    fn remove(&mut self, k: &K) -> Option<C> {
+        HashMap::remove(self, k)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        HashMap::iter(self)
+    }
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self)
+    }
+}
+
+type Key<M> = <M as BucketMap>::Key;
+type Value<M> = <<M as BucketMap>::Bucket as Bucket>::Item;
+
+/// A map from each key to a collection of values. Keys only exist while they
+/// have at least one value, so removing a key's last value removes the key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MultiMap<M: BucketMap> {
+    inner: M,
+    // handed out by `get_or_empty` and indexing for keys with no values
+    empty: M::Bucket,
+}
+
+/// Keys in order, values in a `C`: `BTreeMultiMap<char, Vec<Point>>`.
+pub type BTreeMultiMap<K, C> = MultiMap<BTreeMap<K, C>>;
+/// Keys hashed, values in a `C`: `HashMultiMap<String, HashSet<String>>`.
+pub type HashMultiMap<K, C> = MultiMap<HashMap<K, C>>;
+
+impl<M: BucketMap> MultiMap<M> {
+    pub fn new() -> Self {
+        Self {
+            inner: M::default(),
+            empty: M::Bucket::default(),
+        }
+    }
+
+    /// Adds `v` to `k`'s values. Returns `false` if they're a set that
+    /// already had it.
+    pub fn insert(&mut self, k: Key<M>, v: Value<M>) -> bool {
+        self.inner.bucket(k).put(v)
+    }
+
+    /// Removes one copy of `v` from `k`'s values, and `k` itself if that was
+    /// the last one. Returns whether there was a copy.
+    pub fn remove_one(&mut self, k: &Key<M>, v: &Value<M>) -> bool {
+        let Some(bucket) = self.inner.get_mut(k) else {
+            return false;
+        };
+        let removed = bucket.take(v);
+        if bucket.is_empty() {
+            self.inner.remove(k);
+        }
+        removed
+    }
+
+    /// Removes `k` with all its values.
+    pub fn remove(&mut self, k: &Key<M>) -> Option<M::Bucket> {
+        self.inner.remove(k)
+    }
+
+    /// `None` for a key with no values.
+    pub fn get(&self, k: &Key<M>) -> Option<&M::Bucket> {
+        self.inner.get(k)
+    }
+
+    /// `k`'s values, an empty collection if it has none.
+    pub fn get_or_empty(&self, k: &Key<M>) -> &M::Bucket {
+        self.inner.get(k).unwrap_or(&self.empty)
+    }
+
+    pub fn contains_key(&self, k: &Key<M>) -> bool {
+        self.inner.get(k).is_some()
+    }
+
+    pub fn contains(&self, k: &Key<M>, v: &Value<M>) -> bool {
+        self.inner.get(k).is_some_and(|bucket| bucket.contains(v))
+    }
+
+    /// How many values `k` has.
+    pub fn bucket_len(&self, k: &Key<M>) -> usize {
+        self.inner.get(k).map_or(0, |bucket| bucket.len())
+    }
+
+    /// The number of keys.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// The number of values across every key.
+    pub fn total_len(&self) -> usize {
+        self.inner.iter().map(|(_, bucket)| bucket.len()).sum()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Key<M>> {
+        self.inner.iter().map(|(k, _)| k)
+    }
+
+    /// Each key with all its values.
+    pub fn iter(&self) -> M::Iter<'_> {
+        self.inner.iter()
+    }
+
+    /// Every `(key, value)` pair, one per value.
+    pub fn pairs(&self) -> impl Iterator<Item = (&Key<M>, &Value<M>)> {
+        self.inner
+            .iter()
+            .flat_map(|(k, bucket)| bucket.iter().map(move |v| (k, v)))
+    }
+
+    /// The same pairs the other way round, from each value to the keys that
+    /// had it, in whichever kind of multimap the caller asks for.
+    pub fn invert<N>(&self) -> MultiMap<N>
+    where
+        N: BucketMap<Key = Value<M>>,
+        N::Bucket: Bucket<Item = Key<M>>,
+        Key<M>: Clone,
+        Value<M>: Clone,
+    {
+        self.pairs().map(|(k, v)| (v.clone(), k.clone())).collect()
+    }
+}
+
+/// Keys with no values index to an empty collection.
+impl<M: BucketMap> Index<&Key<M>> for MultiMap<M> {
+    type Output = M::Bucket;
+
+    fn index(&self, k: &Key<M>) -> &M::Bucket {
+        self.get_or_empty(k)
+    }
+}
+
+impl<M: BucketMap> FromIterator<(Key<M>, Value<M>)> for MultiMap<M> {
+    fn from_iter<I: IntoIterator<Item = (Key<M>, Value<M>)>>(iter: I) -> Self {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+
+impl<M: BucketMap> Extend<(Key<M>, Value<M>)> for MultiMap<M> {
+    fn extend<I: IntoIterator<Item = (Key<M>, Value<M>)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<M: BucketMap> IntoIterator for MultiMap<M> {
+    type Item = (Key<M>, M::Bucket);
+    type IntoIter = M::IntoIter;
+
+    fn into_iter(self) -> M::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a, M: BucketMap> IntoIterator for &'a MultiMap<M> {
+    type Item = (&'a Key<M>, &'a M::Bucket);
+    type IntoIter = M::Iter<'a>;
+
+    fn into_iter(self) -> M::Iter<'a> {
+        self.inner.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn vec_buckets_keep_duplicates() {
+        let mut map: BTreeMultiMap<char, Vec<i32>> = [('a', 1), ('b', 2), ('a', 1), ('a', 3)]
+            .into_iter()
+            .collect();
+        assert_eq!(map[&'a'], [1, 1, 3]);
+        assert_eq!(
+            (map.len(), map.total_len(), map.bucket_len(&'a')),
+            (2, 4, 3)
+        );
+        assert!(map.remove_one(&'a', &1));
+        assert_eq!(map.get(&'a'), Some(&vec![1, 3]));
+        assert!(!map.remove_one(&'a', &7));
+        assert!(map.remove_one(&'b', &2));
+        assert!(!map.contains_key(&'b'));
+        assert!(map.get_or_empty(&'b').is_empty());
+        assert_eq!(map.remove(&'a'), Some(vec![1, 3]));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn set_buckets_drop_repeats() {
+        let mut map: HashMultiMap<&str, HashSet<&str>> = HashMultiMap::new();
+        assert!(map.insert("kh", "tc"));
+        assert!(!map.insert("kh", "tc"));
+        assert!(map.insert("kh", "qp"));
+        assert!(map.contains(&"kh", &"qp"));
+        assert!(!map.contains(&"tc", &"kh"));
+        assert_eq!(map.bucket_len(&"kh"), 2);
+        assert_eq!(map.bucket_len(&"zz"), 0);
+    }
+
+    #[test]
+    fn inverts_and_flattens() {
+        let rules: BTreeMultiMap<i32, BTreeSet<i32>> = [(47, 53), (97, 13), (97, 61), (75, 53)]
+            .into_iter()
+            .collect();
+        let pairs: Vec<(i32, i32)> = rules.pairs().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(pairs, [(47, 53), (75, 53), (97, 13), (97, 61)]);
+        let before: BTreeMultiMap<i32, Vec<i32>> = rules.invert();
+        assert_eq!(before[&53], [47, 75]);
+        assert_eq!(before[&61], [97]);
+        assert_eq!(before.len(), 3);
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Insert(u8, u8),
+        RemoveOne(u8, u8),
+        Remove(u8),
+    }
+
+    fn ops() -> impl Strategy<Value = Vec<Op>> {
+        let op = prop_oneof![
+            3 => (0u8..6, 0u8..6).prop_map(|(k, v)| Op::Insert(k, v)),
+            1 => (0u8..6, 0u8..6).prop_map(|(k, v)| Op::RemoveOne(k, v)),
+            1 => (0u8..6).prop_map(Op::Remove),
+        ];
+        prop::collection::vec(op, 0..40)
+    }
+
+    proptest! {
+        /// `Vec` buckets against a list of pairs in insertion order.
+        #[test]
+        fn vec_buckets_match_a_list_of_pairs(ops in ops()) {
+            let mut map: HashMultiMap<u8, Vec<u8>> = HashMultiMap::new();
+            let mut pairs: Vec<(u8, u8)> = Vec::new();
+            for op in ops {
+                match op {
+                    Op::Insert(k, v) => {
+                        prop_assert!(map.insert(k, v));
+                        pairs.push((k, v));
+                    }
+                    Op::RemoveOne(k, v) => {
+                        let found = pairs.iter().position(|p| *p == (k, v));
+                        prop_assert_eq!(map.remove_one(&k, &v), found.is_some());
+                        if let Some(idx) = found {
+                            pairs.remove(idx);
+                        }
+                    }
+                    Op::Remove(k) => {
+                        let had = pairs.iter().any(|(pk, _)| *pk == k);
+                        prop_assert_eq!(map.remove(&k).is_some(), had);
+                        pairs.retain(|(pk, _)| *pk != k);
+                    }
+                }
+            }
+
+            for k in 0u8..6 {
+                let values: Vec<u8> = pairs.iter().filter(|(pk, _)| *pk == k).map(|(_, v)| *v).collect();
+                prop_assert_eq!(map.get_or_empty(&k), &values);
+                prop_assert_eq!(map.contains_key(&k), !values.is_empty());
+                prop_assert_eq!(map.bucket_len(&k), values.len());
+            }
+            let keys: BTreeSet<u8> = pairs.iter().map(|(k, _)| *k).collect();
+            prop_assert_eq!(map.len(), keys.len());
+            prop_assert_eq!(map.total_len(), pairs.len());
+
+            let mut flat: Vec<(u8, u8)> = map.pairs().map(|(k, v)| (*k, *v)).collect();
+            let mut want = pairs.clone();
+            flat.sort();
+            want.sort();
+            prop_assert_eq!(flat, want);
+        }
+
+        /// Set buckets against a set of pairs.
+        #[test]
+        fn set_buckets_match_a_set_of_pairs(ops in ops()) {
+            let mut map: BTreeMultiMap<u8, HashSet<u8>> = BTreeMultiMap::new();
+            let mut pairs: BTreeSet<(u8, u8)> = BTreeSet::new();
+            for op in ops {
+                match op {
+                    Op::Insert(k, v) => prop_assert_eq!(map.insert(k, v), pairs.insert((k, v))),
+                    Op::RemoveOne(k, v) => prop_assert_eq!(map.remove_one(&k, &v), pairs.remove(&(k, v))),
+                    Op::Remove(k) => {
+                        map.remove(&k);
+                        pairs.retain(|(pk, _)| *pk != k);
+                    }
+                }
+            }
+
+            for k in 0u8..6 {
+                for v in 0u8..6 {
+                    prop_assert_eq!(map.contains(&k, &v), pairs.contains(&(k, v)));
+                }
+            }
+            let keys: Vec<u8> = map.keys().copied().collect();
+            let want: Vec<u8> = pairs.iter().map(|(k, _)| *k).collect::<BTreeSet<u8>>().into_iter().collect();
+            prop_assert_eq!(keys, want);
+            prop_assert_eq!(map.total_len(), pairs.len());
+
+            let inverted: BTreeMultiMap<u8, BTreeSet<u8>> = map.invert();
+            let flipped: BTreeSet<(u8, u8)> = inverted.pairs().map(|(v, k)| (*k, *v)).collect();
+            prop_assert_eq!(flipped, pairs);
+        }
+    }
+}
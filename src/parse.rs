@@ -0,0 +1,359 @@
+//! Input parsing that reports where it broke. A `Span` is a piece of the
+//! puzzle input that remembers the whole input it came from, so every error,
+//! however deep in a section or line, says which line and column it's at.
+//!
+//! ```
+//! use aoc2024::parse::Span;
+//!
+//! let input = "47|53\n97|13\n\n75,47,61";
+//! let [rules, updates] = Span::new(input).sections_n().unwrap();
+//! let rules: Vec<(i32, i32)> = rules.lines().map(|l| l.pair("|")).collect::<Result<_, _>>().unwrap();
+//! assert_eq!(rules, [(47, 53), (97, 13)]);
+//! assert_eq!(updates.ints::<i32>().unwrap(), [75, 47, 61]);
+//! ```
+
+use core::fmt;
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::grid::Grid;
+use crate::{parse_token_in, AocHelperError, AocResult};
+
+/// A slice of the input, see the module docs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    input: &'a str,
+    text: &'a str,
+}
+
+impl<'a> Span<'a> {
+    /// The whole input.
+    pub fn new(input: &'a str) -> Self {
+        Self { input, text: input }
+    }
+
+    /// `text` must be a slice of this span's input, otherwise errors in the
+    /// new span point at the start of the input.
+    fn sub(&self, text: &'a str) -> Self {
+        Self {
+            input: self.input,
+            text,
+        }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// True for spans with nothing but whitespace.
+    pub fn is_blank(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    /// 1-based line and column of the span's start.
+    pub fn position(&self) -> (usize, usize) {
+        match self.error("") {
+            AocHelperError::ParseError { line, column, .. } => (line, column),
+            _ => unreachable!("parse_error_in always makes a ParseError"),
+        }
+    }
+
+    /// A parse error pointing at the start of this span.
+    pub fn error(&self, message: impl fmt::Display) -> AocHelperError {
+        AocHelperError::parse_error_in(self.input, self.text, message)
+    }
+
+    pub fn trim(&self) -> Self {
+        self.sub(self.text.trim())
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Span<'a>> + '_ {
+        self.text.lines().map(|line| self.sub(line))
+    }
+
+    /// The runs of lines between blank lines. Extra blank lines, at either
+    /// end or several in a row, never make empty sections.
+    pub fn sections(&self) -> Vec<Span<'a>> {
+        let mut out = Vec::new();
+        let mut section: Option<(usize, usize)> = None;
+        let mut offset = 0;
+        for line in self.text.split_inclusive('\n') {
+            let end = offset + line.trim_end_matches(['\r', '\n']).len();
+            if line.trim().is_empty() {
+                if let Some((start, end)) = section.take() {
+                    out.push(self.sub(&self.text[start..end]));
+                }
+            } else {
+                let start = section.map_or(offset, |(start, _)| start);
+                section = Some((start, end));
+            }
+            offset += line.len();
+        }
+        if let Some((start, end)) = section {
+            out.push(self.sub(&self.text[start..end]));
+        }
+        out
+    }
+
+    /// Exactly `N` sections, for inputs like "rules, blank line, updates".
+    pub fn sections_n<const N: usize>(&self) -> AocResult<[Span<'a>; N]> {
+        let sections = self.sections();
+        if let Some(extra) = sections.get(N) {
+            return Err(extra.error(format!("expected only {} sections", N)));
+        }
+        sections.try_into().map_err(|found: Vec<Span>| {
+            self.end().error(format!(
+                "expected {} sections separated by blank lines, found {}",
+                N,
+                found.len()
+            ))
+        })
+    }
+
+    /// An empty span at the end of this one, for "ran out" errors.
+    fn end(&self) -> Self {
+        self.sub(&self.text[self.text.len()..])
+    }
+
+    /// The parts either side of the first `sep`, for `total: 1 2 3` or
+    /// `k-v` shapes.
+    pub fn split_once(&self, sep: &str) -> AocResult<(Span<'a>, Span<'a>)> {
+        match self.text.split_once(sep) {
+            Some((a, b)) => Ok((self.sub(a), self.sub(b))),
+            None => Err(self.error(format!("expected {:?} in {:?}", sep, self.text))),
+        }
+    }
+
+    pub fn split<'s>(&self, sep: &'s str) -> impl Iterator<Item = Span<'a>> + 's
+    where
+        'a: 's,
+    {
+        let this = *self;
+        self.text.split(sep).map(move |part| this.sub(part))
+    }
+
+    /// The whitespace-separated words.
+    pub fn words(&self) -> impl Iterator<Item = Span<'a>> + '_ {
+        self.text
+            .split_ascii_whitespace()
+            .map(|word| self.sub(word))
+    }
+
+    /// The trimmed span parsed with `FromStr`.
+    pub fn parse<T>(&self) -> AocResult<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        parse_token_in(self.input, self.text)
+    }
+
+    /// Each word parsed, for `3 4 2 1 3` lines.
+    pub fn parse_words<T>(&self) -> AocResult<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.words().map(|word| word.parse()).collect()
+    }
+
+    /// Each part between `sep`s parsed, for `75,47,61` lines.
+    pub fn parse_split<T>(&self, sep: &str) -> AocResult<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.split(sep).map(|part| part.parse()).collect()
+    }
+
+    /// Both sides of `sep` parsed, for `x,y` or `a|b` lines.
+    pub fn pair<T>(&self, sep: &str) -> AocResult<(T, T)>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let (a, b) = self.split_once(sep)?;
+        Ok((a.parse()?, b.parse()?))
+    }
+
+    /// Every integer in the span, ignoring whatever is around them:
+    /// `p=0,4 v=3,-3` gives `[0, 4, 3, -3]`. A `-` right after a digit is
+    /// taken as a separator, so `10-20` gives `[10, 20]`.
+    pub fn ints<T>(&self) -> AocResult<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let bytes = self.text.as_bytes();
+        let mut out = Vec::new();
+        let mut idx = 0;
+        while idx < bytes.len() {
+            let negative = bytes[idx] == b'-'
+                && bytes.get(idx + 1).is_some_and(u8::is_ascii_digit)
+                && (idx == 0 || !bytes[idx - 1].is_ascii_digit());
+            if !negative && !bytes[idx].is_ascii_digit() {
+                idx += 1;
+                continue;
+            }
+            let start = idx;
+            idx += 1;
+            while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+                idx += 1;
+            }
+            out.push(self.sub(&self.text[start..idx]).parse()?);
+        }
+        Ok(out)
+    }
+
+    /// Exactly `N` integers, see `ints`.
+    pub fn ints_n<T, const N: usize>(&self) -> AocResult<[T; N]>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let found = self.ints()?;
+        let count = found.len();
+        found
+            .try_into()
+            .map_err(|_| self.error(format!("expected {} numbers, found {}", N, count)))
+    }
+
+    /// The capture groups of `re`'s first match, group 0 (the whole match)
+    /// included. Groups that took no part in the match are empty spans.
+    pub fn captures(&self, re: &Regex) -> AocResult<Vec<Span<'a>>> {
+        let Some(caps) = re.captures(self.text) else {
+            return Err(self.error(format!("expected a match for {}", re)));
+        };
+        Ok(caps
+            .iter()
+            .map(|group| match group {
+                Some(m) => self.sub(&self.text[m.range()]),
+                None => self.end(),
+            })
+            .collect())
+    }
+
+    /// The span as a `Grid`, see `Grid::parse`.
+    pub fn grid<T>(&self, mut mapper: impl FnMut(char) -> T) -> AocResult<Grid<T>> {
+        self.try_grid(|c| Some(mapper(c)))
+    }
+
+    /// The span as a `Grid`, see `Grid::try_parse`. Errors point into the
+    /// whole input rather than the grid.
+    pub fn try_grid<T>(&self, mapper: impl FnMut(char) -> Option<T>) -> AocResult<Grid<T>> {
+        Grid::try_parse(self.text, mapper).map_err(|e| match e {
+            AocHelperError::ParseError {
+                line,
+                column,
+                message,
+            } => {
+                let (first_line, first_column) = self.position();
+                let column = if line == 1 {
+                    column + first_column - 1
+                } else {
+                    column
+                };
+                AocHelperError::parse_error(line + first_line - 1, column, message)
+            }
+            other => other,
+        })
+    }
+}
+
+impl fmt::Display for Span<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position_of(err: AocHelperError) -> (usize, usize) {
+        match err {
+            AocHelperError::ParseError { line, column, .. } => (line, column),
+            other => panic!("not a parse error: {}", other),
+        }
+    }
+
+    #[test]
+    fn sections_skip_extra_blank_lines() {
+        let input = "\na\nb\n\n\n  \nc\r\n\r\nd\n\n";
+        let sections = Span::new(input).sections();
+        let texts: Vec<&str> = sections.iter().map(|s| s.as_str()).collect();
+        assert_eq!(texts, ["a\nb", "c", "d"]);
+        assert_eq!(sections[1].position(), (7, 1));
+        assert_eq!(sections[2].lines().next().unwrap().position(), (9, 1));
+
+        let err = Span::new(input).sections_n::<2>().unwrap_err();
+        assert_eq!(position_of(err), (9, 1));
+        let err = Span::new("a\nb").sections_n::<2>().unwrap_err();
+        assert_eq!(position_of(err), (2, 2));
+    }
+
+    #[test]
+    fn ints_find_signed_numbers() {
+        let line = Span::new("p=0,4 v=3,-3 and 10-20, -x --5");
+        assert_eq!(line.ints::<i32>().unwrap(), [0, 4, 3, -3, 10, 20, -5]);
+        let [a, b] = Span::new("Button A: X+94, Y+34")
+            .ints_n::<i64, 2>()
+            .unwrap();
+        assert_eq!((a, b), (94, 34));
+
+        let input = "1 2\n3 -400";
+        let err = Span::new(input).ints::<u8>().unwrap_err();
+        assert_eq!(position_of(err), (2, 3));
+        let err = Span::new(input).ints_n::<i32, 3>().unwrap_err();
+        assert_eq!(position_of(err), (1, 1));
+    }
+
+    #[test]
+    fn parses_line_shapes() {
+        let input = "190: 10 19\n3267: 81 40 27\nab-cd\n12,x";
+        let lines: Vec<Span> = Span::new(input).lines().collect();
+        let (total, operands) = lines[1].split_once(":").unwrap();
+        assert_eq!(total.parse::<u64>().unwrap(), 3267);
+        assert_eq!(operands.parse_words::<u64>().unwrap(), [81, 40, 27]);
+
+        let (k, v) = lines[2].split_once("-").unwrap();
+        assert_eq!((k.as_str(), v.as_str()), ("ab", "cd"));
+        assert_eq!(v.position(), (3, 4));
+
+        assert_eq!(position_of(lines[2].split_once(":").unwrap_err()), (3, 1));
+        assert_eq!(position_of(lines[3].pair::<i32>(",").unwrap_err()), (4, 4));
+        assert_eq!(
+            Span::new("75,47,61").parse_split::<i32>(",").unwrap(),
+            [75, 47, 61]
+        );
+    }
+
+    #[test]
+    fn captures_point_into_the_input() {
+        let re = Regex::new(r"Prize: X=(\d+), Y=(\d+)( bonus)?").unwrap();
+        let input = "Button A: X+94, Y+34\nPrize: X=8400, Y=5400";
+        let lines: Vec<Span> = Span::new(input).lines().collect();
+        let caps = lines[1].captures(&re).unwrap();
+        assert_eq!(caps[1].parse::<i32>().unwrap(), 8400);
+        assert_eq!(caps[2].position(), (2, 18));
+        assert!(caps[3].as_str().is_empty());
+        assert_eq!(position_of(lines[0].captures(&re).unwrap_err()), (1, 1));
+    }
+
+    #[test]
+    fn grid_errors_point_into_the_input() {
+        let input = "rules\n\n#.#\n.x.\n";
+        let [_, map] = Span::new(input).sections_n().unwrap();
+        let grid = map.grid(|c| c).unwrap();
+        assert_eq!(grid[(1, 1)], 'x');
+        let err = map
+            .try_grid(|c| match c {
+                '#' => Some(true),
+                '.' => Some(false),
+                _ => None,
+            })
+            .unwrap_err();
+        assert_eq!(position_of(err), (4, 2));
+    }
+}
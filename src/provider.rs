@@ -0,0 +1,381 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tracing::{debug, info};
+
+use crate::{AocHelperError, AocResult};
+
+/// `.env` key holding the adventofcode.com session cookie.
+pub const SESSION_KEY: &str = "AOC_SESSION";
+/// `.env` key to point the fetcher somewhere other than adventofcode.com.
+pub const BASE_URL_KEY: &str = "AOC_BASE_URL";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u32 = 2024;
+/// Minimum gap between two requests to the server.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Somewhere a day's real puzzle input can come from.
+pub trait InputProvider: Send + Sync {
+    fn real_input(&self, day: u32) -> AocResult<String>;
+
+    /// Whether `real_input` is worth trying for `day`, without fetching
+    /// anything. Anything that can fetch says yes.
+    fn has_input(&self, _day: u32) -> bool {
+        true
+    }
+}
+
+/// Inputs already on disk, `<base>/dayN/real_input`.
+#[derive(Debug, Clone)]
+pub struct FileCache {
+    base: PathBuf,
+}
+
+impl FileCache {
+    pub fn new(base: impl Into<PathBuf>) -> Self {
+        Self { base: base.into() }
+    }
+
+    pub fn path(&self, day: u32) -> PathBuf {
+        let mut path = self.base.clone();
+        path.push(format!("day{}", day));
+        path.push("real_input");
+        path
+    }
+
+    /// `Ok(None)` when there is no input yet. An empty file (what
+    /// `makeAday.sh` leaves behind) counts as no input.
+    pub fn get(&self, day: u32) -> AocResult<Option<String>> {
+        let path = self.path(day);
+        match fs::read_to_string(&path) {
+            Ok(input) if input.is_empty() => Ok(None),
+            Ok(input) => Ok(Some(input)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(AocHelperError::FileReadError { path, source }),
+        }
+    }
+
+    pub fn store(&self, day: u32, input: &str) -> AocResult<()> {
+        let path = self.path(day);
+        let write = |path: &PathBuf| -> io::Result<()> {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, input)
+        };
+        write(&path).map_err(|source| AocHelperError::FileWriteError { path, source })
+    }
+}
+
+impl InputProvider for FileCache {
+    fn real_input(&self, day: u32) -> AocResult<String> {
+        match self.get(day)? {
+            Some(input) => Ok(input),
+            None => Err(AocHelperError::FileReadError {
+                path: self.path(day),
+                source: io::Error::new(io::ErrorKind::NotFound, "no input cached"),
+            }),
+        }
+    }
+
+    /// A non-empty file, the same rule as `get`.
+    fn has_input(&self, day: u32) -> bool {
+        fs::metadata(self.path(day)).is_ok_and(|meta| meta.is_file() && meta.len() > 0)
+    }
+}
+
+/// Spaces requests out by at least `min_interval`. Share one between
+/// fetchers (see `RateLimiter::shared`) so a batch over every day stays polite.
+#[derive(Debug)]
+pub struct RateLimiter {
+    min_interval: Duration,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last_request: Mutex::new(None),
+        }
+    }
+
+    /// The process-wide limiter, `DEFAULT_MIN_INTERVAL` apart.
+    pub fn shared() -> Arc<RateLimiter> {
+        static SHARED: OnceLock<Arc<RateLimiter>> = OnceLock::new();
+        SHARED
+            .get_or_init(|| Arc::new(RateLimiter::new(DEFAULT_MIN_INTERVAL)))
+            .clone()
+    }
+
+    /// Blocks until a request is allowed, then records it.
+    pub fn wait(&self) {
+        let mut last_request = self.last_request.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(last) = *last_request {
+            let since = last.elapsed();
+            if since < self.min_interval {
+                let pause = self.min_interval - since;
+                debug!("Waiting {:?} before the next request", pause);
+                thread::sleep(pause);
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+}
+
+/// Downloads inputs from adventofcode.com (or `base_url`) with a session cookie.
+pub struct HttpFetcher {
+    base_url: String,
+    session: String,
+    limiter: Arc<RateLimiter>,
+    agent: ureq::Agent,
+}
+
+impl HttpFetcher {
+    pub fn new(base_url: &str, session: &str, limiter: Arc<RateLimiter>) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("aoc2024-runner/", env!("CARGO_PKG_VERSION")))
+            .build();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+            limiter,
+            agent,
+        }
+    }
+
+    /// `None` when no session token is configured.
+    pub fn from_env() -> Option<Self> {
+        let _ = dotenvy::dotenv();
+        let session = env::var(SESSION_KEY)
+            .ok()
+            .filter(|s| !s.trim().is_empty())?;
+        let base_url = env::var(BASE_URL_KEY).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        Some(HttpFetcher::new(&base_url, &session, RateLimiter::shared()))
+    }
+
+    pub fn url(&self, day: u32) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, YEAR, day)
+    }
+}
+
+impl InputProvider for HttpFetcher {
+    fn real_input(&self, day: u32) -> AocResult<String> {
+        let url = self.url(day);
+        self.limiter.wait();
+        info!("Fetching {}", url);
+        let fetch_error = |message: String| AocHelperError::FetchError { day, message };
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()
+            .map_err(|e| match e {
+                ureq::Error::Status(code, _) => fetch_error(format!("{} returned {}", url, code)),
+                ureq::Error::Transport(e) => fetch_error(e.to_string()),
+            })?;
+        response
+            .into_string()
+            .map_err(|e| fetch_error(e.to_string()))
+    }
+}
+
+/// Serves from the `FileCache` and, when an input is missing, fetches it from
+/// `upstream` once and keeps it forever.
+pub struct CachingProvider {
+    cache: FileCache,
+    upstream: Option<Box<dyn InputProvider>>,
+}
+
+impl CachingProvider {
+    pub fn new(cache: FileCache, upstream: Option<Box<dyn InputProvider>>) -> Self {
+        Self { cache, upstream }
+    }
+
+    /// Cache under `base`, fetching over HTTP if a session token is set.
+    pub fn from_env(base: impl Into<PathBuf>) -> Self {
+        let upstream = HttpFetcher::from_env().map(|f| Box::new(f) as Box<dyn InputProvider>);
+        CachingProvider::new(FileCache::new(base), upstream)
+    }
+}
+
+impl InputProvider for CachingProvider {
+    fn real_input(&self, day: u32) -> AocResult<String> {
+        if let Some(input) = self.cache.get(day)? {
+            return Ok(input);
+        }
+        let Some(upstream) = &self.upstream else {
+            return self.cache.real_input(day);
+        };
+        let input = upstream.real_input(day)?;
+        self.cache.store(day, &input)?;
+        info!(
+            "Cached day {} input at {}",
+            day,
+            self.cache.path(day).display()
+        );
+        Ok(input)
+    }
+
+    fn has_input(&self, day: u32) -> bool {
+        self.upstream.is_some() || self.cache.has_input(day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Serves `responses` in order, one per connection, and records each
+    /// request's header block.
+    struct MockServer {
+        url: String,
+        requests: Arc<Mutex<Vec<String>>>,
+        hits: Arc<AtomicUsize>,
+    }
+
+    impl MockServer {
+        fn start(responses: Vec<(u16, &'static str)>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let hits = Arc::new(AtomicUsize::new(0));
+            let (thread_requests, thread_hits) = (requests.clone(), hits.clone());
+            thread::spawn(move || {
+                for (status, body) in responses {
+                    let Ok((stream, _)) = listener.accept() else {
+                        return;
+                    };
+                    let mut reader = BufReader::new(stream);
+                    let mut head = String::new();
+                    loop {
+                        let mut line = String::new();
+                        if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                            break;
+                        }
+                        head.push_str(&line);
+                    }
+                    thread_requests.lock().unwrap().push(head);
+                    thread_hits.fetch_add(1, Ordering::SeqCst);
+                    let mut stream = reader.into_inner();
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                }
+            });
+            MockServer {
+                url,
+                requests,
+                hits,
+            }
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc2024-provider-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn fetcher(server: &MockServer) -> HttpFetcher {
+        HttpFetcher::new(
+            &server.url,
+            "abc123",
+            Arc::new(RateLimiter::new(Duration::ZERO)),
+        )
+    }
+
+    #[test]
+    fn fetches_once_and_caches_forever() {
+        let server = MockServer::start(vec![(200, "1 2 3\n")]);
+        let dir = temp_dir("cache");
+        let provider = CachingProvider::new(FileCache::new(&dir), Some(Box::new(fetcher(&server))));
+
+        assert_eq!(provider.real_input(7).unwrap(), "1 2 3\n");
+        assert_eq!(provider.real_input(7).unwrap(), "1 2 3\n");
+        assert_eq!(server.hits.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            fs::read_to_string(dir.join("day7/real_input")).unwrap(),
+            "1 2 3\n"
+        );
+
+        let request = server.requests.lock().unwrap()[0].clone();
+        assert!(
+            request.starts_with("GET /2024/day/7/input HTTP/1.1"),
+            "{}",
+            request
+        );
+        assert!(request.contains("session=abc123"), "{}", request);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn empty_cache_file_is_refetched() {
+        let server = MockServer::start(vec![(200, "input")]);
+        let dir = temp_dir("empty");
+        fs::create_dir_all(dir.join("day3")).unwrap();
+        fs::write(dir.join("day3/real_input"), "").unwrap();
+        let provider = CachingProvider::new(FileCache::new(&dir), Some(Box::new(fetcher(&server))));
+
+        assert_eq!(provider.real_input(3).unwrap(), "input");
+        assert_eq!(server.hits.load(Ordering::SeqCst), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn errors_are_not_cached() {
+        let server = MockServer::start(vec![(404, "not yet"), (200, "finally")]);
+        let dir = temp_dir("errors");
+        let provider = CachingProvider::new(FileCache::new(&dir), Some(Box::new(fetcher(&server))));
+
+        let err = provider.real_input(25).unwrap_err();
+        assert!(
+            matches!(err, AocHelperError::FetchError { day: 25, .. }),
+            "{}",
+            err
+        );
+        assert!(!dir.join("day25/real_input").exists());
+        assert_eq!(provider.real_input(25).unwrap(), "finally");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_input_without_upstream_is_a_read_error() {
+        let dir = temp_dir("offline");
+        let provider = CachingProvider::new(FileCache::new(&dir), None);
+        let err = provider.real_input(1).unwrap_err();
+        assert!(
+            matches!(err, AocHelperError::FileReadError { .. }),
+            "{}",
+            err
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn requests_are_spaced_out() {
+        let server = MockServer::start(vec![(200, "a"), (200, "b")]);
+        let interval = Duration::from_millis(200);
+        let fetcher = HttpFetcher::new(&server.url, "abc123", Arc::new(RateLimiter::new(interval)));
+
+        let start = Instant::now();
+        assert_eq!(fetcher.real_input(1).unwrap(), "a");
+        assert_eq!(fetcher.real_input(2).unwrap(), "b");
+        assert!(start.elapsed() >= interval);
+    }
+}
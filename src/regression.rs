@@ -0,0 +1,311 @@
+//! Regression checks for `cargo test`: every registered day, run against
+//! every input that has an expected answer in its `answers.toml`.
+//!
+//! Every day needs its example in `test_input` and an `answers.toml`, both
+//! checked in under `static/`; a day missing either fails the check, as does
+//! having no inputs directory at all. A day whose `answers.toml` records
+//! nothing yet, as `aoc new-day` leaves it, is skipped. Real inputs are
+//! optional and checked when they're on disk with a recorded answer.
+
+use core::fmt;
+use std::fs;
+use std::path::Path;
+
+use tracing::info;
+
+use crate::answers::{AnswerStatus, Answers};
+use crate::runner::{run_part, Registry, RunResult};
+use crate::solution::Part;
+use crate::{AocHelper, AocHelperError};
+
+/// What `check` ran and what it couldn't.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// One per (day, part, input) with an expected answer.
+    pub results: Vec<RunResult>,
+    /// Why days (or, with day 0, the whole check) couldn't be checked.
+    pub missing: Vec<(u32, String)>,
+    /// Days with no answers recorded yet, which don't count as failures.
+    pub skipped: Vec<(u32, String)>,
+}
+
+impl Report {
+    /// Anything that didn't produce its expected answer: wrong answers,
+    /// errors, panics and parts that aren't implemented.
+    pub fn failures(&self) -> impl Iterator<Item = &RunResult> {
+        self.results
+            .iter()
+            .filter(|result| result.status != Some(AnswerStatus::Pass))
+    }
+
+    pub fn passed(&self) -> usize {
+        self.results.len() - self.failures().count()
+    }
+
+    /// Panics with the whole report if anything failed or is missing.
+    pub fn assert_passing(&self) {
+        if self.failures().next().is_some() || !self.missing.is_empty() {
+            panic!("regression check failed:\n{}", self);
+        }
+        info!("{}", self);
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in self.failures() {
+            writeln!(f, "{}", result)?;
+        }
+        for (day, reason) in self.missing.iter() {
+            match day {
+                0 => writeln!(f, "missing: {}", reason)?,
+                day => writeln!(f, "day {} missing: {}", day, reason)?,
+            }
+        }
+        for (day, reason) in self.skipped.iter() {
+            writeln!(f, "day {} skipped: {}", day, reason)?;
+        }
+        write!(
+            f,
+            "{} passed, {} failed, {} missing, {} skipped",
+            self.passed(),
+            self.results.len() - self.passed(),
+            self.missing.len(),
+            self.skipped.len()
+        )
+    }
+}
+
+/// Checks every day in `registry` against the inputs found the usual way,
+/// see `AocHelper::find_base_path`.
+pub fn check(registry: &Registry) -> Report {
+    check_in(registry, None)
+}
+
+/// Like `check`, with inputs from `base_path` when it's given.
+pub fn check_in(registry: &Registry, base_path: Option<&Path>) -> Report {
+    let mut report = Report::default();
+    for day in registry.days() {
+        let aoc = match AocHelper::with_base_path(base_path, day, None) {
+            Ok(aoc) => aoc,
+            Err(e @ AocHelperError::NoInputDir { .. }) => {
+                // the same for every day, so say it once
+                report.missing = vec![(0, e.to_string())];
+                return report;
+            }
+            Err(e) => {
+                report.missing.push((day, e.to_string()));
+                continue;
+            }
+        };
+
+        let example = aoc.get_test_input_path(None).filter(|path| path.is_file());
+        let answers = aoc.get_answers_path();
+        if example.is_none() || !answers.is_file() {
+            let what = if example.is_none() {
+                "no example in test_input"
+            } else {
+                "no answers.toml"
+            };
+            report.missing.push((day, what.to_string()));
+            continue;
+        }
+        match fs::read_to_string(&answers).map(|raw| Answers::parse(&raw)) {
+            Ok(Ok(recorded)) if recorded.is_empty() => {
+                report
+                    .skipped
+                    .push((day, "no answers recorded yet".to_string()));
+                continue;
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => {
+                report
+                    .missing
+                    .push((day, format!("bad answers.toml: {}", e)));
+                continue;
+            }
+            Err(e) => {
+                report
+                    .missing
+                    .push((day, format!("can't read answers.toml: {}", e)));
+                continue;
+            }
+        }
+
+        let before = report.results.len();
+        for part in Part::ALL {
+            for input in aoc.available_inputs() {
+                if aoc.get_expected_answer(&input, part).is_none() {
+                    continue;
+                }
+                let answer = match registry.get(day, part) {
+                    Some(factory) => {
+                        let budget = registry.timeouts().budget(day, part);
+                        run_part(&aoc, factory, part, &input, budget)
+                    }
+                    None => Ok(None),
+                };
+                let status = match &answer {
+                    Ok(Some(answer)) => Some(aoc.check_answer(&input, part, answer)),
+                    _ => None,
+                };
+                report.results.push(RunResult {
+                    day,
+                    part,
+                    input,
+                    answer,
+                    status,
+                });
+            }
+        }
+        if report.results.len() == before {
+            report
+                .missing
+                .push((day, "no inputs with expected answers".to_string()));
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+    use crate::solution::Solution;
+    use crate::AocResult;
+
+    /// Day 1: sums the numbers for part 1, panics on part 2.
+    #[derive(Default)]
+    struct Summer {
+        total: i64,
+    }
+
+    impl Solution for Summer {
+        fn day(&self) -> u32 {
+            1
+        }
+
+        fn parse(&mut self, input: &str) -> AocResult<()> {
+            self.total = crate::parse::Span::new(input).ints::<i64>()?.iter().sum();
+            Ok(())
+        }
+
+        fn part1(&mut self) -> Option<String> {
+            Some(self.total.to_string())
+        }
+
+        fn part2(&mut self) -> Option<String> {
+            panic!("part 2 blew up");
+        }
+    }
+
+    /// Day 2: nothing implemented.
+    #[derive(Default)]
+    struct Unsolved;
+
+    impl Solution for Unsolved {
+        fn day(&self) -> u32 {
+            2
+        }
+
+        fn parse(&mut self, _input: &str) -> AocResult<()> {
+            Ok(())
+        }
+    }
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn checks_inputs_with_answers() {
+        let dir = env::temp_dir().join(format!("aoc2024-regression-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        write(&dir.join("day1/test_input"), "1 2 3");
+        write(&dir.join("day1/test_input_b"), "4 5");
+        write(&dir.join("day1/real_input"), "10 20");
+        write(
+            &dir.join("day1/answers.toml"),
+            "[test_input]\npart1 = 6\npart2 = 1\n[test_input_b]\npart1 = 10\n[real_input]\npart1 = 30\n",
+        );
+        write(&dir.join("day2/test_input"), "x");
+
+        let mut registry = Registry::new();
+        registry.register::<Summer>();
+        registry.register::<Unsolved>();
+        let report = check_in(&registry, Some(&dir));
+
+        let checked: Vec<String> = report.results.iter().map(|r| r.to_string()).collect();
+        assert_eq!(
+            checked,
+            [
+                "day 1 part 1 [test]: 6 (pass)",
+                "day 1 part 1 [test_input_b]: 9 (FAIL, expected 10)",
+                "day 1 part 1 [real]: 30 (pass)",
+                "day 1 part 2 [test]: error: Solution panicked: part 2 blew up",
+            ]
+        );
+        assert_eq!((report.passed(), report.failures().count()), (2, 2));
+        assert_eq!(report.missing, [(2, "no answers.toml".to_string())]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_day_without_its_example_fails() {
+        let dir =
+            env::temp_dir().join(format!("aoc2024-regression-example-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        write(&dir.join("day1/real_input"), "10 20");
+        write(&dir.join("day1/answers.toml"), "[real_input]\npart1 = 30\n");
+
+        let mut registry = Registry::new();
+        registry.register::<Summer>();
+        let report = check_in(&registry, Some(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(report.results.is_empty());
+        assert_eq!(
+            report.missing,
+            [(1, "no example in test_input".to_string())]
+        );
+        assert!(report
+            .to_string()
+            .ends_with("0 passed, 0 failed, 1 missing, 0 skipped"));
+    }
+
+    #[test]
+    fn unreadable_answers_fail_but_empty_ones_skip() {
+        let dir =
+            env::temp_dir().join(format!("aoc2024-regression-answers-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        write(&dir.join("day1/test_input"), "1 2 3");
+        write(&dir.join("day1/answers.toml"), "[test_input\n");
+        write(&dir.join("day2/test_input"), "x");
+        write(&dir.join("day2/answers.toml"), "# part1 = 0\n");
+
+        let mut registry = Registry::new();
+        registry.register::<Summer>();
+        registry.register::<Unsolved>();
+        let report = check_in(&registry, Some(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].0, 1);
+        assert!(report.missing[0].1.starts_with("bad answers.toml"));
+        assert_eq!(report.skipped, [(2, "no answers recorded yet".to_string())]);
+    }
+
+    #[test]
+    #[should_panic(expected = "regression check failed")]
+    fn fails_without_inputs() {
+        let mut registry = Registry::new();
+        registry.register::<Summer>();
+        let missing = env::temp_dir().join("aoc2024-regression-missing");
+        let report = check_in(&registry, Some(&missing));
+        assert!(report.results.is_empty());
+        assert_eq!(report.missing.len(), 1);
+        report.assert_passing();
+    }
+}
@@ -0,0 +1,312 @@
+use core::fmt;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::answers::AnswerStatus;
+use crate::solution::{Part, Solution};
+use crate::timeout::{run_catching_panics, Timeouts};
+use crate::{AocHelper, AocResult, RequestedAocInputType};
+
+pub type SolutionFactory = fn() -> Box<dyn Solution>;
+
+fn make_solution<S: Solution + Default + 'static>() -> Box<dyn Solution> {
+    Box::new(S::default())
+}
+
+/// Maps (day, part) to the solution that answers it.
+///
+/// Most days register a single solution for both parts; days whose part 2
+/// lives in its own crate (day9b, day24b) register that crate for
+/// `Part::Two` only. Later registrations replace earlier ones.
+///
+/// Also holds the time budget for each (day, part), see `Timeouts`.
+#[derive(Default)]
+pub struct Registry {
+    days: BTreeMap<u32, BTreeMap<Part, SolutionFactory>>,
+    timeouts: Timeouts,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<S: Solution + Default + 'static>(&mut self) {
+        for part in Part::ALL {
+            self.register_part::<S>(part);
+        }
+    }
+
+    pub fn register_part<S: Solution + Default + 'static>(&mut self, part: Part) {
+        let day = S::default().day();
+        self.days
+            .entry(day)
+            .or_default()
+            .insert(part, make_solution::<S>);
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = u32> + '_ {
+        self.days.keys().copied()
+    }
+
+    pub fn contains_day(&self, day: u32) -> bool {
+        self.days.contains_key(&day)
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<SolutionFactory> {
+        self.days.get(&day)?.get(&part).copied()
+    }
+
+    pub fn timeouts(&self) -> &Timeouts {
+        &self.timeouts
+    }
+
+    pub fn timeouts_mut(&mut self) -> &mut Timeouts {
+        &mut self.timeouts
+    }
+
+    pub fn set_timeout(&mut self, day: u32, part: Part, budget: Duration) {
+        self.timeouts.set_part(day, part, budget);
+    }
+}
+
+#[derive(Debug)]
+pub struct RunResult {
+    pub day: u32,
+    pub part: Part,
+    pub input: RequestedAocInputType,
+    /// `Ok(None)` means nothing is registered for this part.
+    pub answer: AocResult<Option<String>>,
+    /// Compared against `answers.toml`, only set when there is an answer.
+    pub status: Option<AnswerStatus>,
+}
+
+impl RunResult {
+    /// Errors and wrong answers; unknown answers don't count.
+    pub fn is_failure(&self) -> bool {
+        self.answer.is_err() || matches!(self.status, Some(AnswerStatus::Fail { .. }))
+    }
+}
+
+impl fmt::Display for RunResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {} {} [{}]: ", self.day, self.part, self.input)?;
+        match &self.answer {
+            Ok(Some(answer)) => {
+                write!(f, "{}", answer)?;
+                if let Some(status) = &self.status {
+                    write!(f, " ({})", status)?;
+                }
+                Ok(())
+            }
+            Ok(None) => write!(f, "not implemented"),
+            Err(e) => write!(f, "error: {}", e),
+        }
+    }
+}
+
+/// Parses and solves on a worker thread, giving up after `budget` (`None`
+/// waits forever). Reading the input isn't counted. A panic comes back as
+/// `SolutionPanicked`.
+pub fn run_part(
+    aoc: &AocHelper,
+    factory: SolutionFactory,
+    part: Part,
+    input_type: &RequestedAocInputType,
+    budget: Option<Duration>,
+) -> AocResult<Option<String>> {
+    let input = aoc.get_input_as_string(input_type.clone())?;
+    let input_type = input_type.clone();
+    run_catching_panics(budget, move || {
+        let mut solution = factory();
+        solution.configure(&input_type);
+        solution.parse(&input)?;
+        Ok(solution.solve(part))
+    })
+}
+
+/// Every test case found in the day's directory, then the real input when
+/// it's on disk or can be fetched.
+pub fn default_inputs(aoc: &AocHelper) -> Vec<RequestedAocInputType> {
+    let mut inputs: Vec<RequestedAocInputType> = aoc.test_cases().collect();
+    if aoc.has_real_input() {
+        inputs.push(RequestedAocInputType::Real);
+    }
+    inputs
+}
+
+/// Runs every requested part of `day` against every requested input (or
+/// `default_inputs` when `inputs` is `None`), parts first, in the same order
+/// the per-day binaries always used. Fails only when the day's inputs can't
+/// be located at all.
+pub fn run_day(
+    registry: &Registry,
+    day: u32,
+    parts: &[Part],
+    inputs: Option<&[RequestedAocInputType]>,
+) -> AocResult<Vec<RunResult>> {
+    let custom_inputs: Vec<String> = inputs
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|input| match input {
+            RequestedAocInputType::CustomTest { fname } => Some(fname.clone()),
+            _ => None,
+        })
+        .collect();
+    let aoc = AocHelper::new(day, Some(custom_inputs))?;
+    let inputs: Vec<RequestedAocInputType> = match inputs {
+        Some(inputs) => inputs.to_vec(),
+        None => default_inputs(&aoc),
+    };
+
+    let mut results: Vec<RunResult> = Vec::new();
+    for part in parts {
+        for input in inputs.iter() {
+            let answer = match registry.get(day, *part) {
+                Some(factory) => run_part(
+                    &aoc,
+                    factory,
+                    *part,
+                    input,
+                    registry.timeouts().budget(day, *part),
+                ),
+                None => Ok(None),
+            };
+            let status = match &answer {
+                Ok(Some(answer)) => Some(aoc.check_answer(input, *part, answer)),
+                _ => None,
+            };
+            results.push(RunResult {
+                day,
+                part: *part,
+                input: input.clone(),
+                answer,
+                status,
+            });
+        }
+    }
+    Ok(results)
+}
+
+/// Entry point for the per-day binaries: both parts, every test case then
+/// the real input.
+pub fn run_standalone<S: Solution + Default + 'static>() {
+    let mut registry = Registry::new();
+    registry.register::<S>();
+    let day = S::default().day();
+    match run_day(&registry, day, &Part::ALL, None) {
+        Ok(results) => {
+            for result in results {
+                println!("{}", result);
+            }
+        }
+        Err(e) => eprintln!("day {}: {}", day, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+    use crate::bench::bench_part;
+    use crate::provider::{CachingProvider, FileCache, InputProvider};
+    use crate::{AocHelperError, AocResult};
+
+    /// Day 4: answers part 1, panics on part 2.
+    #[derive(Default)]
+    struct HalfDone;
+
+    impl Solution for HalfDone {
+        fn day(&self) -> u32 {
+            4
+        }
+
+        fn parse(&mut self, _input: &str) -> AocResult<()> {
+            Ok(())
+        }
+
+        fn part1(&mut self) -> Option<String> {
+            Some("ok".to_string())
+        }
+
+        fn part2(&mut self) -> Option<String> {
+            panic!("part 2 blew up");
+        }
+    }
+
+    fn temp_inputs(name: &str) -> std::path::PathBuf {
+        let dir = env::temp_dir().join(format!("aoc2024-runner-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("day4")).unwrap();
+        fs::write(dir.join("day4/test_input"), "x").unwrap();
+        dir
+    }
+
+    fn assert_panicked<T: fmt::Debug>(result: AocResult<T>) {
+        match result {
+            Err(AocHelperError::SolutionPanicked { message }) => {
+                assert_eq!(message, "part 2 blew up")
+            }
+            other => panic!("expected SolutionPanicked, got {:?}", other),
+        }
+    }
+
+    /// Never asked for anything here.
+    struct Unreachable;
+
+    impl InputProvider for Unreachable {
+        fn real_input(&self, _day: u32) -> AocResult<String> {
+            unreachable!("default_inputs doesn't fetch")
+        }
+    }
+
+    fn names(inputs: &[RequestedAocInputType]) -> Vec<String> {
+        inputs.iter().map(|input| input.to_string()).collect()
+    }
+
+    #[test]
+    fn real_input_only_when_it_can_be_had() {
+        let dir = temp_inputs("defaults");
+        let helper = |upstream: Option<Box<dyn InputProvider>>| {
+            let provider = CachingProvider::new(FileCache::new(&dir), upstream);
+            AocHelper::with_base_path(Some(&dir), 4, None)
+                .unwrap()
+                .with_provider(Box::new(provider))
+        };
+
+        let missing = default_inputs(&helper(None));
+        let fetchable = default_inputs(&helper(Some(Box::new(Unreachable))));
+        fs::write(dir.join("day4/real_input"), "").unwrap();
+        let empty = default_inputs(&helper(None));
+        fs::write(dir.join("day4/real_input"), "1 2 3").unwrap();
+        let on_disk = default_inputs(&helper(None));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(names(&missing), ["test"]);
+        assert_eq!(names(&fetchable), ["test", "real"]);
+        assert_eq!(names(&empty), ["test"]);
+        assert_eq!(names(&on_disk), ["test", "real"]);
+    }
+
+    #[test]
+    fn panics_are_per_part_errors() {
+        let dir = temp_inputs("panics");
+        let aoc = AocHelper::with_base_path(Some(&dir), 4, None).unwrap();
+        let mut registry = Registry::new();
+        registry.register::<HalfDone>();
+        let factory = |part| registry.get(4, part).unwrap();
+        let test = RequestedAocInputType::Test;
+
+        let part2 = run_part(&aoc, factory(Part::Two), Part::Two, &test, None);
+        let part1 = run_part(&aoc, factory(Part::One), Part::One, &test, None);
+        let bench2 = bench_part(&aoc, &registry, 4, Part::Two, &test, 2);
+        let bench1 = bench_part(&aoc, &registry, 4, Part::One, &test, 2);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_panicked(part2);
+        assert_eq!(part1.unwrap().as_deref(), Some("ok"));
+        assert_panicked(bench2);
+        assert_eq!(bench1.unwrap().unwrap().answer, "ok");
+    }
+}
@@ -0,0 +1,493 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tracing::info;
+
+use crate::{AocHelperError, AocResult, DAYS, TEST_INPUT_PREFIX};
+
+const CARGO_TEMPLATE: &str = r#"[package]
+name = "day{day}"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+aoc2024 = {path = "../../"}
+tracing = "0.1.41"
+"#;
+
+const MAIN_TEMPLATE: &str = r#"use aoc2024::{runner::run_standalone, solution::Solution, AocResult};
+use tracing::debug;
+
+struct Day{day} {
+    lines: Vec<String>,
+}
+
+impl Day{day} {
+    fn parse(input: &str) -> AocResult<Self> {
+        let lines = input.lines().map(|l| l.to_string()).collect();
+        Ok(Self { lines })
+    }
+
+    fn part1(&self) -> Option<String> {
+        debug!("{} lines", self.lines.len());
+        None
+    }
+
+    fn part2(&self) -> Option<String> {
+        None
+    }
+}
+
+#[derive(Default)]
+pub struct Day{day}Solution {
+    d{day}: Option<Day{day}>,
+}
+
+impl Solution for Day{day}Solution {
+    fn day(&self) -> u32 {
+        {day}
+    }
+
+    fn parse(&mut self, input: &str) -> AocResult<()> {
+        self.d{day} = Some(Day{day}::parse(input)?);
+        Ok(())
+    }
+
+    fn part1(&mut self) -> Option<String> {
+        self.d{day}.as_ref()?.part1()
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        self.d{day}.as_ref()?.part2()
+    }
+}
+
+fn main() {
+    run_standalone::<Day{day}Solution>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn parses_example() {
+        let mut solution = Day{day}Solution::default();
+        solution.parse(EXAMPLE).unwrap();
+    }
+
+    #[test]
+    #[ignore = "fill in EXAMPLE and the expected answer"]
+    fn part1_example() {
+        let mut solution = Day{day}Solution::default();
+        solution.parse(EXAMPLE).unwrap();
+        assert_eq!(solution.part1().as_deref(), Some(""));
+    }
+}
+"#;
+
+const ANSWERS_TEMPLATE: &str = r#"# Expected answers, checked by `aoc run` and `cargo test`
+# [test_input]
+# part1 = 0
+# part2 = 0
+#
+# [real_input]
+# part1 = 0
+"#;
+
+/// Generates a new day: the `days/dayN` crate with a `Solution` skeleton,
+/// empty input files and an `answers.toml` stub in the inputs directory, and
+/// the day's entry in the `aoc` binary's registry.
+///
+/// Nothing is written if the crate or the registration already exists. Input
+/// files that are already there (a downloaded input, say) are left alone.
+#[derive(Debug, Clone)]
+pub struct DayScaffold {
+    pub day: u32,
+    pub workspace: PathBuf,
+    pub inputs: PathBuf,
+}
+
+impl DayScaffold {
+    pub fn new(day: u32, workspace: &Path, inputs: &Path) -> AocResult<Self> {
+        if !DAYS.contains(&day) {
+            return Err(AocHelperError::InvalidDay { day });
+        }
+        Ok(Self {
+            day,
+            workspace: workspace.to_path_buf(),
+            inputs: inputs.to_path_buf(),
+        })
+    }
+
+    pub fn crate_dir(&self) -> PathBuf {
+        self.workspace.join("days").join(format!("day{}", self.day))
+    }
+
+    pub fn registry_path(&self) -> PathBuf {
+        self.workspace.join("src/bin/aoc/registry.rs")
+    }
+
+    /// Writes everything and returns the paths it created.
+    pub fn create(&self) -> AocResult<Vec<PathBuf>> {
+        let crate_dir = self.crate_dir();
+        if crate_dir.exists() {
+            return Err(AocHelperError::DayExists {
+                day: self.day,
+                path: crate_dir,
+            });
+        }
+        let registry_path = self.registry_path();
+        let registry =
+            fs::read_to_string(&registry_path).map_err(|source| AocHelperError::FileReadError {
+                path: registry_path.clone(),
+                source,
+            })?;
+        // worked out up front so a registry we can't edit stops us before
+        // anything is written
+        let registry = register_day(&registry, self.day, &registry_path)?;
+
+        let mut created = Vec::new();
+        let day_str = self.day.to_string();
+        let render = |template: &str| template.replace("{day}", &day_str);
+        write_new(&crate_dir.join("Cargo.toml"), &render(CARGO_TEMPLATE))?;
+        write_new(&crate_dir.join("src/main.rs"), &render(MAIN_TEMPLATE))?;
+        created.push(crate_dir);
+
+        let input_dir = self.inputs.join(format!("day{}", self.day));
+        let placeholders = [
+            (TEST_INPUT_PREFIX, ""),
+            ("real_input", ""),
+            ("answers.toml", ANSWERS_TEMPLATE),
+        ];
+        for (name, contents) in placeholders {
+            let path = input_dir.join(name);
+            if path.exists() {
+                info!("Keeping existing {}", path.display());
+                continue;
+            }
+            write_new(&path, contents)?;
+            created.push(path);
+        }
+
+        fs::write(&registry_path, registry).map_err(|source| AocHelperError::FileWriteError {
+            path: registry_path.clone(),
+            source,
+        })?;
+        created.push(registry_path);
+        Ok(created)
+    }
+}
+
+fn write_new(path: &Path, contents: &str) -> AocResult<()> {
+    let write_error = |source| AocHelperError::FileWriteError {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(write_error)?;
+    }
+    fs::write(path, contents).map_err(write_error)
+}
+
+/// The day number in `line` right after `prefix`, ignoring any suffix: 9 for
+/// `mod day9b;`.
+fn day_in(line: &str, prefix: &str) -> Option<u32> {
+    let rest = line.trim().strip_prefix(prefix)?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Adds `mod dayN;` and its `register` call to the registry source, each in
+/// day order next to the existing ones.
+fn register_day(source: &str, day: u32, path: &Path) -> AocResult<String> {
+    let mut lines: Vec<String> = source.lines().map(|l| l.to_string()).collect();
+    let mod_line = format!("mod day{};", day);
+    if lines.iter().any(|l| l.trim() == mod_line) {
+        return Err(AocHelperError::DayExists {
+            day,
+            path: path.to_path_buf(),
+        });
+    }
+
+    let mods: Vec<(usize, u32)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, l)| day_in(l, "mod day").map(|d| (idx, d)))
+        .collect();
+    let registers: Vec<(usize, u32)> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.trim().starts_with("registry.register"))
+        .filter_map(|(idx, l)| {
+            let start = l.find("::<day")? + "::<".len();
+            day_in(&l[start..], "day").map(|d| (idx, d))
+        })
+        .collect();
+    let (Some(last_mod), Some(last_register)) = (mods.last(), registers.last()) else {
+        return Err(AocHelperError::ScaffoldError {
+            path: path.to_path_buf(),
+            message: "no `mod dayN;` and `registry.register` lines to add the day next to"
+                .to_string(),
+        });
+    };
+
+    // each mod comes with an `#[allow]` and a `#[path]` line above it
+    let mod_at = mods
+        .iter()
+        .find(|(_, d)| *d > day)
+        .map(|(idx, _)| idx.saturating_sub(2))
+        .unwrap_or(last_mod.0 + 1);
+    let register_at = registers
+        .iter()
+        .find(|(_, d)| *d > day)
+        .map(|(idx, _)| *idx)
+        .unwrap_or(last_register.0 + 1);
+    let indent: String = lines[last_register.0]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect();
+
+    // the register line sits below the mods, insert it first so mod_at stays put
+    lines.insert(
+        register_at,
+        format!(
+            "{}registry.register::<day{}::Day{}Solution>();",
+            indent, day, day
+        ),
+    );
+    let mod_block = [
+        "#[allow(unused, nonstandard_style, clippy::all)]".to_string(),
+        format!("#[path = \"../../../days/day{}/src/main.rs\"]", day),
+        mod_line,
+    ];
+    for (offset, line) in mod_block.into_iter().enumerate() {
+        lines.insert(mod_at + offset, line);
+    }
+
+    let mut out = lines.join("\n");
+    if source.ends_with('\n') {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::regression;
+    use crate::runner::Registry;
+    use crate::solution::Solution;
+
+    const REGISTRY: &str = r#"use aoc2024::runner::Registry;
+
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day2/src/main.rs"]
+mod day2;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day9/src/main.rs"]
+mod day9;
+#[allow(unused, nonstandard_style, clippy::all)]
+#[path = "../../../days/day9b/src/main.rs"]
+mod day9b;
+
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<day2::Day2Solution>();
+    registry.register::<day9::Day9Solution>();
+    registry.register_part::<day9b::Day9Solution>(Part::Two);
+    registry
+}
+"#;
+
+    fn register(day: u32) -> AocResult<String> {
+        register_day(REGISTRY, day, Path::new("registry.rs"))
+    }
+
+    /// The days in `mod` lines, then in `register` lines, in file order.
+    fn days_in(source: &str) -> (Vec<String>, Vec<String>) {
+        let mods = source
+            .lines()
+            .filter_map(|l| l.strip_prefix("mod "))
+            .map(|l| l.trim_end_matches(';').to_string())
+            .collect();
+        let registers = source
+            .lines()
+            .filter(|l| l.trim().starts_with("registry.register"))
+            .filter_map(|l| l.split("::<").nth(1)?.split("::").next())
+            .map(|d| d.to_string())
+            .collect();
+        (mods, registers)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc2024-scaffold-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn day_in_ignores_suffixes() {
+        assert_eq!(day_in("mod day9b;", "mod day"), Some(9));
+        assert_eq!(day_in("  mod day12;", "mod day"), Some(12));
+        assert_eq!(day_in("day24b::Day24Solution>", "day"), Some(24));
+        assert_eq!(day_in("mod days;", "mod day"), None);
+        assert_eq!(day_in("fn main() {}", "mod day"), None);
+    }
+
+    #[test]
+    fn inserts_before_the_first_day() {
+        let out = register(1).unwrap();
+        let (mods, registers) = days_in(&out);
+        assert_eq!(mods, ["day1", "day2", "day9", "day9b"]);
+        assert_eq!(registers, ["day1", "day2", "day9", "day9b"]);
+        assert!(out.contains(
+            "#[allow(unused, nonstandard_style, clippy::all)]\n\
+             #[path = \"../../../days/day1/src/main.rs\"]\n\
+             mod day1;\n"
+        ));
+        assert!(out.contains("\n    registry.register::<day1::Day1Solution>();\n"));
+    }
+
+    #[test]
+    fn inserts_between_days() {
+        let (mods, registers) = days_in(&register(5).unwrap());
+        assert_eq!(mods, ["day2", "day5", "day9", "day9b"]);
+        assert_eq!(registers, ["day2", "day5", "day9", "day9b"]);
+    }
+
+    #[test]
+    fn inserts_after_the_last_day_and_its_suffixed_twin() {
+        let out = register(10).unwrap();
+        let (mods, registers) = days_in(&out);
+        assert_eq!(mods, ["day2", "day9", "day9b", "day10"]);
+        assert_eq!(registers, ["day2", "day9", "day9b", "day10"]);
+        assert!(out.contains("mod day10;\n\npub fn registry()"));
+        assert!(out.contains("registry.register::<day10::Day10Solution>();\n    registry\n}"));
+    }
+
+    #[test]
+    fn keeps_the_trailing_newline() {
+        assert!(register(5).unwrap().ends_with("}\n"));
+        let trimmed = REGISTRY.trim_end();
+        let out = register_day(trimmed, 5, Path::new("registry.rs")).unwrap();
+        assert!(out.ends_with('}'));
+        assert_eq!(out.lines().count(), trimmed.lines().count() + 4);
+    }
+
+    #[test]
+    fn refuses_a_registered_day() {
+        match register(9) {
+            Err(AocHelperError::DayExists { day: 9, path }) => {
+                assert_eq!(path, Path::new("registry.rs"))
+            }
+            other => panic!("expected DayExists, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn needs_mod_and_register_lines() {
+        let no_mods =
+            "pub fn registry() -> Registry {\n    registry.register::<day2::Day2Solution>();\n}\n";
+        let no_registers = "mod day2;\n\npub fn registry() -> Registry {\n    Registry::new()\n}\n";
+        for source in ["", no_mods, no_registers] {
+            match register_day(source, 5, Path::new("registry.rs")) {
+                Err(AocHelperError::ScaffoldError { path, .. }) => {
+                    assert_eq!(path, Path::new("registry.rs"))
+                }
+                other => panic!("expected ScaffoldError, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn create_writes_nothing_when_the_registry_cannot_be_edited() {
+        let workspace = temp_dir("workspace");
+        let inputs = temp_dir("inputs");
+        let registry_path = workspace.join("src/bin/aoc/registry.rs");
+        write_new(&registry_path, "pub fn registry() {}\n").unwrap();
+
+        let scaffold = DayScaffold::new(5, &workspace, &inputs).unwrap();
+        let result = scaffold.create();
+        let crate_written = scaffold.crate_dir().exists();
+        let inputs_written = inputs.exists();
+        let registry = fs::read_to_string(&registry_path).unwrap();
+        fs::remove_dir_all(&workspace).unwrap();
+
+        assert!(matches!(result, Err(AocHelperError::ScaffoldError { .. })));
+        assert!(!crate_written);
+        assert!(!inputs_written);
+        assert_eq!(registry, "pub fn registry() {}\n");
+    }
+
+    /// What `create` registers for day 5, before anything is solved.
+    #[derive(Default)]
+    struct Fresh;
+
+    impl Solution for Fresh {
+        fn day(&self) -> u32 {
+            5
+        }
+
+        fn parse(&mut self, _input: &str) -> AocResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn a_new_day_passes_the_regression_check() {
+        let workspace = temp_dir("regression");
+        let inputs = workspace.join("static");
+        write_new(&workspace.join("src/bin/aoc/registry.rs"), REGISTRY).unwrap();
+        DayScaffold::new(5, &workspace, &inputs)
+            .unwrap()
+            .create()
+            .unwrap();
+
+        let mut registry = Registry::new();
+        registry.register::<Fresh>();
+        let report = regression::check_in(&registry, Some(&inputs));
+        fs::remove_dir_all(&workspace).unwrap();
+
+        assert!(report.results.is_empty());
+        assert!(report.missing.is_empty());
+        assert_eq!(report.skipped, [(5, "no answers recorded yet".to_string())]);
+        report.assert_passing();
+    }
+
+    #[test]
+    fn create_writes_the_day() {
+        let workspace = temp_dir("create");
+        let inputs = workspace.join("static");
+        let registry_path = workspace.join("src/bin/aoc/registry.rs");
+        write_new(&registry_path, REGISTRY).unwrap();
+
+        let scaffold = DayScaffold::new(5, &workspace, &inputs).unwrap();
+        let created = scaffold.create().unwrap();
+        let registry = fs::read_to_string(&registry_path).unwrap();
+        let main = fs::read_to_string(scaffold.crate_dir().join("src/main.rs")).unwrap();
+        let again = scaffold.create();
+        fs::remove_dir_all(&workspace).unwrap();
+
+        assert_eq!(
+            created,
+            [
+                scaffold.crate_dir(),
+                inputs.join("day5/test_input"),
+                inputs.join("day5/real_input"),
+                inputs.join("day5/answers.toml"),
+                registry_path,
+            ]
+        );
+        assert_eq!(registry, register(5).unwrap());
+        assert!(main.contains("pub struct Day5Solution"));
+        assert!(matches!(
+            again,
+            Err(AocHelperError::DayExists { day: 5, .. })
+        ));
+    }
+}
@@ -0,0 +1,330 @@
+//! Searches over anything implementing `Neighbors` or `WeightedNeighbors`: a
+//! stored `Graph`, a `Grid<bool>` or a closure, so grid puzzles can search
+//! without building a `Graph` first.
+//!
+//! Every search takes any number of starts, and the targeted ones stop at the
+//! first node `is_goal` accepts, which covers multi-source and multi-target
+//! puzzles with the same functions.
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+use tracing::debug;
+
+use crate::graph::{Neighbors, WeightedNeighbors};
+
+/// Step counts from a breadth-first search, see `bfs`.
+#[derive(Debug, Clone)]
+pub struct BfsResult<T: Hash + Eq + Clone> {
+    distances: HashMap<T, usize>,
+    prevs: HashMap<T, T>,
+}
+
+impl<T: Hash + Eq + Clone> BfsResult<T> {
+    /// Steps from the nearest start, `None` when `node` can't be reached.
+    pub fn distance(&self, node: &T) -> Option<usize> {
+        self.distances.get(node).copied()
+    }
+
+    /// Every reached node with its step count, starts included at 0.
+    pub fn distances(&self) -> &HashMap<T, usize> {
+        &self.distances
+    }
+
+    /// One shortest path from the nearest start to `node`, both ends included.
+    pub fn path_to(&self, node: &T) -> Option<Vec<T>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+        Some(walk_back(node, &self.prevs))
+    }
+}
+
+/// Follows `prevs` back from `end` to a node with no predecessor, which is
+/// one of the starts.
+fn walk_back<T: Hash + Eq + Clone>(end: &T, prevs: &HashMap<T, T>) -> Vec<T> {
+    let mut out = vec![end.clone()];
+    while let Some(prev) = prevs.get(&out[out.len() - 1]) {
+        out.push(prev.clone());
+    }
+    out.reverse();
+    out
+}
+
+/// Breadth-first search from every start at once, to everywhere they lead.
+pub fn bfs<T, N>(graph: &N, starts: impl IntoIterator<Item = T>) -> BfsResult<T>
+where
+    T: Hash + Eq + Clone,
+    N: Neighbors<T> + ?Sized,
+{
+    let (distances, prevs, _) = bfs_until(graph, starts, |_| false);
+    BfsResult { distances, prevs }
+}
+
+/// Breadth-first search that stops at the first node `is_goal` accepts and
+/// returns a shortest path to it, both ends included. Its step count is one
+/// less than the path's length.
+pub fn bfs_to<T, N>(
+    graph: &N,
+    starts: impl IntoIterator<Item = T>,
+    is_goal: impl FnMut(&T) -> bool,
+) -> Option<Vec<T>>
+where
+    T: Hash + Eq + Clone,
+    N: Neighbors<T> + ?Sized,
+{
+    let (_, prevs, goal) = bfs_until(graph, starts, is_goal);
+    Some(walk_back(&goal?, &prevs))
+}
+
+type BfsState<T> = (HashMap<T, usize>, HashMap<T, T>, Option<T>);
+
+fn bfs_until<T, N>(
+    graph: &N,
+    starts: impl IntoIterator<Item = T>,
+    mut is_goal: impl FnMut(&T) -> bool,
+) -> BfsState<T>
+where
+    T: Hash + Eq + Clone,
+    N: Neighbors<T> + ?Sized,
+{
+    let mut distances: HashMap<T, usize> = HashMap::new();
+    let mut prevs: HashMap<T, T> = HashMap::new();
+    let mut queue: VecDeque<T> = VecDeque::new();
+    for start in starts {
+        if distances.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(cur) = queue.pop_front() {
+        if is_goal(&cur) {
+            return (distances, prevs, Some(cur));
+        }
+        let next_distance = distances[&cur] + 1;
+        for next in graph.neighbors(&cur) {
+            if distances.contains_key(&next) {
+                continue;
+            }
+            distances.insert(next.clone(), next_distance);
+            prevs.insert(next.clone(), cur.clone());
+            queue.push_back(next);
+        }
+    }
+
+    debug!("BFS reached {} nodes", distances.len());
+    (distances, prevs, None)
+}
+
+/// A node waiting in the A* queue. Ordered so `BinaryHeap` pops the lowest
+/// estimate first, and the one furthest along among equal estimates; the node
+/// itself never takes part, so it doesn't have to be `Ord`.
+struct Queued<T> {
+    estimate: i32,
+    cost: i32,
+    node: T,
+}
+
+impl<T> Ord for Queued<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then(self.cost.cmp(&other.cost))
+    }
+}
+
+impl<T> PartialOrd for Queued<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> PartialEq for Queued<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Queued<T> {}
+
+/// A* through `graph` from any of `starts` to the first node `is_goal`
+/// accepts, with `heuristic` estimating the remaining cost from a node. Returns the total cost and one
+/// cheapest path, both ends included, or `None` when no goal can be reached.
+///
+/// The answer is only guaranteed cheapest if the heuristic never
+/// overestimates; Manhattan distance on a grid of unit steps is the usual
+/// choice. Costs must not be negative.
+pub fn astar<T, N>(
+    graph: &N,
+    starts: impl IntoIterator<Item = T>,
+    mut is_goal: impl FnMut(&T) -> bool,
+    mut heuristic: impl FnMut(&T) -> i32,
+) -> Option<(i32, Vec<T>)>
+where
+    T: Hash + Eq + Clone,
+    N: WeightedNeighbors<T> + ?Sized,
+{
+    let mut costs: HashMap<T, i32> = HashMap::new();
+    let mut prevs: HashMap<T, T> = HashMap::new();
+    let mut queue: BinaryHeap<Queued<T>> = BinaryHeap::new();
+    for start in starts {
+        if costs.insert(start.clone(), 0).is_none() {
+            let estimate = heuristic(&start);
+            queue.push(Queued {
+                estimate,
+                cost: 0,
+                node: start,
+            });
+        }
+    }
+
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        if costs.get(&node).is_some_and(|best| cost > *best) {
+            // stale entry, node was queued again later with a lower cost
+            continue;
+        }
+        if is_goal(&node) {
+            return Some((cost, walk_back(&node, &prevs)));
+        }
+        for (next, step_cost) in graph.weighted_neighbors(&node) {
+            let alt = cost + step_cost;
+            if costs.get(&next).is_none_or(|best| alt < *best) {
+                costs.insert(next.clone(), alt);
+                prevs.insert(next.clone(), node.clone());
+                let estimate = alt + heuristic(&next);
+                queue.push(Queued {
+                    estimate,
+                    cost: alt,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    debug!("A* found no goal after reaching {} nodes", costs.len());
+    None
+}
+
+/// Dijkstra from any of `starts` to the first node `is_goal` accepts: `astar`
+/// without a heuristic.
+pub fn dijkstra_to<T, N>(
+    graph: &N,
+    starts: impl IntoIterator<Item = T>,
+    is_goal: impl FnMut(&T) -> bool,
+) -> Option<(i32, Vec<T>)>
+where
+    T: Hash + Eq + Clone,
+    N: WeightedNeighbors<T> + ?Sized,
+{
+    astar(graph, starts, is_goal, |_| 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Grid, Pos};
+
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#..
+.####.#.
+......#E";
+
+    fn open_neighbors(grid: &Grid<char>) -> impl Fn(&Pos) -> Vec<Pos> + '_ {
+        |pos| grid.neighbors4(*pos).filter(|n| grid[*n] != '#').collect()
+    }
+
+    fn manhattan(a: Pos, b: Pos) -> i32 {
+        (a.0.abs_diff(b.0) + a.1.abs_diff(b.1)) as i32
+    }
+
+    fn assert_walk(grid: &Grid<char>, path: &[Pos]) {
+        for step in path.windows(2) {
+            assert_eq!(manhattan(step[0], step[1]), 1, "{:?}", step);
+            assert_ne!(grid[step[1]], '#');
+        }
+    }
+
+    #[test]
+    fn bfs_distances_and_paths() {
+        let grid = Grid::parse(MAZE, |c| c).unwrap();
+        let start = grid.find(&'S').unwrap();
+        let end = grid.find(&'E').unwrap();
+        let result = bfs(&open_neighbors(&grid), [start]);
+        assert_eq!(result.distance(&start), Some(0));
+        assert_eq!(result.distance(&end), Some(15));
+        let path = result.path_to(&end).unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!((path[0], path[15]), (start, end));
+        assert_walk(&grid, &path);
+    }
+
+    #[test]
+    fn bfs_from_several_starts_uses_the_nearest() {
+        let grid = Grid::parse(MAZE, |c| c).unwrap();
+        let end = grid.find(&'E').unwrap();
+        let result = bfs(&open_neighbors(&grid), [(0, 0), (0, 7)]);
+        assert_eq!(result.distance(&end), Some(4));
+        assert_eq!(result.path_to(&end).unwrap()[0], (0, 7));
+    }
+
+    #[test]
+    fn bfs_to_stops_at_the_nearest_goal() {
+        let grid = Grid::parse(MAZE, |c| c).unwrap();
+        let path = bfs_to(&open_neighbors(&grid), [(0, 0)], |p| p.1 == 4).unwrap();
+        assert_eq!(path.last(), Some(&(2, 4)));
+        assert_eq!(path.len(), 7);
+        assert_eq!(bfs_to(&open_neighbors(&grid), [(0, 0)], |_| false), None);
+    }
+
+    #[test]
+    fn astar_matches_bfs() {
+        let grid = Grid::parse(MAZE, |c| c).unwrap();
+        let start = grid.find(&'S').unwrap();
+        let end = grid.find(&'E').unwrap();
+        let expected = bfs(&open_neighbors(&grid), [start]);
+        let open = open_neighbors(&grid);
+        let unit_steps = |p: &Pos| open(p).into_iter().map(|n| (n, 1));
+        for pos in grid.positions().filter(|p| grid[*p] != '#') {
+            let found = astar(&unit_steps, [start], |p| *p == pos, |p| manhattan(*p, pos));
+            let Some((cost, path)) = found else {
+                assert_eq!(expected.distance(&pos), None, "to {:?}", pos);
+                continue;
+            };
+            assert_eq!(Some(cost as usize), expected.distance(&pos), "to {:?}", pos);
+            assert_eq!(path.len(), cost as usize + 1);
+            assert_walk(&grid, &path);
+        }
+        assert_eq!(expected.distance(&end), Some(15));
+    }
+
+    #[test]
+    fn searches_a_grid_directly() {
+        let grid = Grid::parse(MAZE, |c| c != '#').unwrap();
+        let end = (4, 7);
+        assert_eq!(bfs(&grid, [(0, 0)]).distance(&end), Some(15));
+        let (cost, path) = astar(&grid, [(0, 0)], |p| *p == end, |p| manhattan(*p, end)).unwrap();
+        assert_eq!(cost, 15);
+        assert_walk(&grid.map(|open| if *open { '.' } else { '#' }), &path);
+        // walls lead nowhere, even from inside one
+        assert_eq!(bfs(&grid, [(0, 3)]).distances().len(), 1);
+    }
+
+    #[test]
+    fn dijkstra_takes_the_cheaper_detour() {
+        // a -> b -> d costs 2 + 2, the direct a -> d edge costs 5
+        let edges: HashMap<char, Vec<(char, i32)>> =
+            HashMap::from([('a', vec![('b', 2), ('d', 5)]), ('b', vec![('d', 2)])]);
+        let next = |n: &char| edges.get(n).cloned().unwrap_or_default();
+        assert_eq!(
+            dijkstra_to(&next, ['a'], |n| *n == 'd'),
+            Some((4, vec!['a', 'b', 'd']))
+        );
+        assert_eq!(dijkstra_to(&next, ['b'], |n| *n == 'a'), None);
+    }
+}
@@ -0,0 +1,65 @@
+use core::fmt;
+
+use crate::{AocResult, RequestedAocInputType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn from_number(n: u32) -> Option<Part> {
+        match n {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(&self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "part {}", self.number())
+    }
+}
+
+/// A single day's puzzle, as seen by the runner.
+///
+/// The runner builds a fresh instance for every (input, part) pair, calls
+/// `configure` and `parse` once, and then asks for exactly one part. A part
+/// that this solution doesn't answer returns `None`. Malformed input should
+/// come back from `parse` as an `AocHelperError::ParseError`, not a panic.
+pub trait Solution {
+    fn day(&self) -> u32;
+
+    /// Hook for puzzles whose parameters differ between the example and the
+    /// real input (grid bounds, step counts...). Called before `parse`.
+    fn configure(&mut self, _input_type: &RequestedAocInputType) {}
+
+    fn parse(&mut self, input: &str) -> AocResult<()>;
+
+    fn part1(&mut self) -> Option<String> {
+        None
+    }
+
+    fn part2(&mut self) -> Option<String> {
+        None
+    }
+
+    fn solve(&mut self, part: Part) -> Option<String> {
+        match part {
+            Part::One => self.part1(),
+            Part::Two => self.part2(),
+        }
+    }
+}
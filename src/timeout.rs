@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::env;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+use crate::solution::Part;
+use crate::{AocHelperError, AocResult};
+
+/// Budget for any (day, part) that doesn't set its own.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Wall-clock budgets per (day, part).
+///
+/// Lookup order, first hit wins:
+/// 1. `AOC_TIMEOUT_DAY<d>_PART<p>` from the environment
+/// 2. `AOC_TIMEOUT_DAY<d>` from the environment
+/// 3. a budget set for the part with `set_part`
+/// 4. a budget set for the day with `set_day`
+/// 5. `AOC_TIMEOUT` from the environment
+/// 6. the default passed to `new`
+///
+/// Environment values are in seconds and may be fractional; `0` means no limit.
+#[derive(Debug, Clone)]
+pub struct Timeouts {
+    default: Option<Duration>,
+    days: HashMap<u32, Duration>,
+    parts: HashMap<(u32, Part), Duration>,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self::new(Some(DEFAULT_TIMEOUT))
+    }
+}
+
+impl Timeouts {
+    /// `None` means solutions may run forever unless something overrides it.
+    pub fn new(default: Option<Duration>) -> Self {
+        Self {
+            default,
+            days: HashMap::new(),
+            parts: HashMap::new(),
+        }
+    }
+
+    pub fn set_day(&mut self, day: u32, budget: Duration) {
+        self.days.insert(day, budget);
+    }
+
+    pub fn set_part(&mut self, day: u32, part: Part, budget: Duration) {
+        self.parts.insert((day, part), budget);
+    }
+
+    /// `None` means no limit.
+    pub fn budget(&self, day: u32, part: Part) -> Option<Duration> {
+        self.budget_with_env(day, part, |key| env::var(key).ok())
+    }
+
+    /// `budget` with environment variables looked up by `var`.
+    fn budget_with_env(
+        &self,
+        day: u32,
+        part: Part,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Option<Duration> {
+        let from_env = |key: &str| parse_budget(key, var(key)?);
+        let part_key = format!("AOC_TIMEOUT_DAY{}_PART{}", day, part.number());
+        if let Some(budget) = from_env(&part_key) {
+            return budget;
+        }
+        if let Some(budget) = from_env(&format!("AOC_TIMEOUT_DAY{}", day)) {
+            return budget;
+        }
+        if let Some(budget) = self.parts.get(&(day, part)).or_else(|| self.days.get(&day)) {
+            return Some(*budget);
+        }
+        if let Some(budget) = from_env("AOC_TIMEOUT") {
+            return budget;
+        }
+        self.default
+    }
+}
+
+/// `Some(None)` is an explicit "no limit", `None` means `raw` isn't a number
+/// of seconds, which gets a warning.
+fn parse_budget(key: &str, raw: String) -> Option<Option<Duration>> {
+    match raw.trim().parse::<f64>() {
+        Ok(0.0) => Some(None),
+        Ok(secs) if secs > 0.0 && secs.is_finite() => Some(Some(Duration::from_secs_f64(secs))),
+        _ => {
+            warn!("Ignoring {}={:?}, expected a number of seconds", key, raw);
+            None
+        }
+    }
+}
+
+/// Runs `f` on its own thread and waits at most `budget` for it.
+///
+/// On timeout `TimeoutError` comes back with the time waited, but the worker
+/// thread is abandoned, not stopped: there's no way to cancel it from the
+/// outside, so it keeps running (and holding whatever `f` captured) until `f`
+/// returns or the process exits. Its result is then thrown away.
+///
+/// Panics inside `f` are passed on to the caller as usual. With no budget `f`
+/// just runs on the calling thread.
+pub fn run_with_timeout<T, F>(budget: Option<Duration>, f: F) -> AocResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> AocResult<T> + Send + 'static,
+{
+    let Some(budget) = budget else {
+        return f();
+    };
+
+    let start = Instant::now();
+    let (tx, rx) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        // the receiver is gone once we've timed out, nobody wants the answer
+        let _ = tx.send(f());
+    });
+
+    match rx.recv_timeout(budget) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(AocHelperError::TimeoutError {
+            elapsed: start.elapsed(),
+        }),
+        Err(mpsc::RecvTimeoutError::Disconnected) => match handle.join() {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(()) => unreachable!("the worker always sends before exiting"),
+        },
+    }
+}
+
+/// `run_with_timeout`, with a panic in `f` coming back as `SolutionPanicked`
+/// rather than unwinding through the caller, so one broken day can't take
+/// down a whole `run all`.
+pub fn run_catching_panics<T, F>(budget: Option<Duration>, f: F) -> AocResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> AocResult<T> + Send + 'static,
+{
+    panic::catch_unwind(AssertUnwindSafe(|| run_with_timeout(budget, f)))
+        .unwrap_or_else(|panic| Err(AocHelperError::from_panic(panic)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    const SEC: Duration = Duration::from_secs(1);
+
+    fn budget(
+        timeouts: &Timeouts,
+        vars: &[(&str, &str)],
+        day: u32,
+        part: Part,
+    ) -> Option<Duration> {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        timeouts.budget_with_env(day, part, |key| vars.get(key).map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn part_beats_day_beats_default() {
+        let mut timeouts = Timeouts::new(Some(SEC));
+        timeouts.set_day(6, 5 * SEC);
+        timeouts.set_part(6, Part::Two, 9 * SEC);
+        assert_eq!(budget(&timeouts, &[], 6, Part::Two), Some(9 * SEC));
+        assert_eq!(budget(&timeouts, &[], 6, Part::One), Some(5 * SEC));
+        assert_eq!(budget(&timeouts, &[], 7, Part::One), Some(SEC));
+    }
+
+    #[test]
+    fn no_default_means_no_limit() {
+        let mut timeouts = Timeouts::new(None);
+        assert_eq!(budget(&timeouts, &[], 1, Part::One), None);
+        timeouts.set_day(1, SEC);
+        assert_eq!(budget(&timeouts, &[], 1, Part::One), Some(SEC));
+        assert_eq!(Timeouts::default().default, Some(DEFAULT_TIMEOUT));
+    }
+
+    #[test]
+    fn environment_overrides_in_order() {
+        let mut timeouts = Timeouts::new(Some(SEC));
+        timeouts.set_part(6, Part::Two, 9 * SEC);
+        let all = [
+            ("AOC_TIMEOUT_DAY6_PART2", "2"),
+            ("AOC_TIMEOUT_DAY6", "3"),
+            ("AOC_TIMEOUT", "4"),
+        ];
+        assert_eq!(budget(&timeouts, &all, 6, Part::Two), Some(2 * SEC));
+        assert_eq!(budget(&timeouts, &all[1..], 6, Part::Two), Some(3 * SEC));
+        // a budget set in code beats `AOC_TIMEOUT`, which beats the default
+        assert_eq!(budget(&timeouts, &all[2..], 6, Part::Two), Some(9 * SEC));
+        assert_eq!(budget(&timeouts, &all[2..], 6, Part::One), Some(4 * SEC));
+    }
+
+    #[test]
+    fn environment_values() {
+        let timeouts = Timeouts::new(Some(SEC));
+        let get = |raw| budget(&timeouts, &[("AOC_TIMEOUT", raw)], 1, Part::One);
+        assert_eq!(get("0"), None);
+        assert_eq!(get(" 0.5 "), Some(SEC / 2));
+        // nonsense is ignored, falling through to the default
+        assert_eq!(get("soon"), Some(SEC));
+        assert_eq!(get("-3"), Some(SEC));
+    }
+
+    #[test]
+    fn finishes_within_budget() {
+        assert_eq!(run_with_timeout(Some(10 * SEC), || Ok(42)).unwrap(), 42);
+        let failed: AocResult<()> = run_with_timeout(Some(10 * SEC), || {
+            Err(AocHelperError::SolutionPanicked {
+                message: "not really".to_string(),
+            })
+        });
+        assert!(matches!(
+            failed,
+            Err(AocHelperError::SolutionPanicked { .. })
+        ));
+    }
+
+    #[test]
+    fn no_budget_runs_on_this_thread() {
+        let caller = thread::current().id();
+        let ran_on = run_with_timeout(None, move || Ok(thread::current().id())).unwrap();
+        assert_eq!(ran_on, caller);
+    }
+
+    #[test]
+    fn times_out() {
+        let budget = Duration::from_millis(20);
+        let result = run_with_timeout(Some(budget), || {
+            thread::sleep(SEC);
+            Ok(())
+        });
+        match result {
+            Err(AocHelperError::TimeoutError { elapsed }) => {
+                assert!(elapsed >= budget && elapsed < SEC)
+            }
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn passes_panics_on() {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            run_with_timeout::<(), _>(Some(10 * SEC), || panic!("worker blew up"))
+        }));
+        let panic = result.expect_err("the panic should reach the caller");
+        assert_eq!(panic.downcast_ref::<&str>(), Some(&"worker blew up"));
+    }
+
+    #[test]
+    fn catches_panics_as_errors() {
+        for budget in [Some(10 * SEC), None] {
+            match run_catching_panics::<(), _>(budget, || panic!("worker blew up")) {
+                Err(AocHelperError::SolutionPanicked { message }) => {
+                    assert_eq!(message, "worker blew up")
+                }
+                other => panic!("expected SolutionPanicked, got {:?}", other),
+            }
+        }
+        assert_eq!(run_catching_panics(None, || Ok(7)).unwrap(), 7);
+    }
+}